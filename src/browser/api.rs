@@ -11,7 +11,8 @@ use crate::models::{Error, ErrorInfo};
 ///
 /// - Uses `options.user_data_dir` as the Chrome user data directory.
 /// - Disables the Chrome sandbox (`--no-sandbox`).
-/// - Sets the window size to 1920x1080.
+/// - Sets the window size to 1920x1080 without a default viewport override, so tabs
+///   use the window size unless they request their own viewport.
 ///
 /// # Errors
 ///
@@ -22,6 +23,8 @@ pub async fn launch(options: LaunchOptions) -> Result<(Arc<Browser>, Handler), E
   let config = BrowserConfig::builder()
     .user_data_dir(PathBuf::from(&options.user_data_dir))
     .no_sandbox()
+    .window_size(1920, 1080)
+    .viewport(None)
     .build()
     .map_err(|e| {
       Error::Operation(ErrorInfo {
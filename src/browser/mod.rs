@@ -1,3 +1,4 @@
 pub mod api;
 pub mod models;
+pub mod session;
 pub mod tab;
@@ -0,0 +1,251 @@
+use chaser_oxide::Browser;
+use chaser_oxide::cdp::browser_protocol::browser::BrowserContextId;
+use chaser_oxide::cdp::browser_protocol::target::CreateBrowserContextParams;
+use futures::TryFutureExt;
+use futures::future;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::time::SystemTime;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::browser::session::dto::{CreateSessionDto, SessionDto};
+use crate::browser::session::models::Session;
+use crate::browser::tab;
+use crate::browser::tab::dto::ProxyDto;
+use crate::browser::tab::models::to_unix_millis;
+use crate::browser::tab::network;
+use crate::models::{Error, ErrorInfo};
+
+static SESSIONS: LazyLock<Mutex<HashMap<String, Session>>> =
+  LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Finds the browser context and the proxy of a session by the session ID.
+///
+/// # Arguments
+///
+/// - `session_id`: The ID of the session to look up.
+///
+/// # Errors
+///
+/// Returns `Error::NotFound` if the session with the given ID does not exist.
+///
+/// # Examples
+///
+/// ```ignore
+/// let (context_id, proxy) = api::find(session_id).await?;
+/// ```
+pub async fn find(session_id: &str) -> Result<(BrowserContextId, Option<ProxyDto>), Error> {
+  SESSIONS
+    .lock()
+    .await
+    .get(session_id)
+    .map(|session| (session.context_id.clone(), session.proxy.clone()))
+    .ok_or_else(|| Error::NotFound(format!("session_id {session_id}")))
+}
+
+/// Holds the session with the given ID open until the returned guard is dropped.
+///
+/// `close` takes the session out of the store under the same lock before it collects the
+/// session's tabs, so a tab registered while the guard is held is closed with the session.
+///
+/// # Errors
+///
+/// Returns `Error::NotFound` if the session does not exist or is being closed.
+///
+/// # Examples
+///
+/// ```ignore
+/// let _session = api::hold_open(session_id).await?;
+/// ```
+pub(crate) async fn hold_open(session_id: &str) -> Result<impl Sized + use<>, Error> {
+  let sessions = SESSIONS.lock().await;
+  if sessions.contains_key(session_id) {
+    Ok(sessions)
+  } else {
+    Err(Error::NotFound(format!("session_id {session_id}")))
+  }
+}
+
+/// Creates a new session backed by its own incognito browser context.
+///
+/// # Behavior
+///
+/// - Creates a browser context (`Target.createBrowserContext`), routed through
+///   `dto.proxy` when provided.
+/// - Stores the session in the in-memory store.
+/// - Tabs opened with the returned `session_id` share cookies, storage and cache with each
+///   other while staying isolated from other sessions and from the default profile.
+///
+/// # Arguments
+///
+/// - `browser`: The shared browser instance.
+/// - `dto`: Session payload with the optional proxy.
+///
+/// # Errors
+///
+/// Returns an `Error` if:
+/// - The proxy is invalid (see `network::context_params`).
+/// - Creating the browser context fails.
+///
+/// # Examples
+///
+/// ```ignore
+/// let session = api::create(browser, CreateSessionDto::default()).await?;
+/// ```
+pub async fn create(browser: Arc<Browser>, dto: CreateSessionDto) -> Result<SessionDto, Error> {
+  async fn create_context(
+    (browser, params, proxy): (Arc<Browser>, CreateBrowserContextParams, Option<ProxyDto>),
+  ) -> Result<Session, Error> {
+    browser
+      .create_browser_context(params)
+      .await
+      .map(|context_id| Session {
+        context_id,
+        browser: browser.clone(),
+        created_at: SystemTime::now(),
+        proxy,
+      })
+      .map_err(|e| {
+        Error::Operation(ErrorInfo {
+          message: format!("Failed to create browser context: {e}"),
+          code: None,
+        })
+      })
+  }
+  async fn store_session(session: Session) -> Result<SessionDto, Error> {
+    let session_id = Uuid::new_v4().to_string();
+    let created_at = to_unix_millis(session.created_at);
+    SESSIONS.lock().await.insert(session_id.clone(), session);
+    Ok(SessionDto {
+      session_id,
+      created_at,
+      tabs: Vec::new(),
+    })
+  }
+
+  future::ready(
+    network::context_params(dto.proxy.as_ref()).map(|params| (browser, params, dto.proxy)),
+  )
+  .and_then(create_context)
+  .and_then(store_session)
+  .await
+}
+
+/// Lists all sessions with the IDs of their open tabs.
+///
+/// # Examples
+///
+/// ```ignore
+/// let sessions = api::list().await;
+/// ```
+pub async fn list() -> Vec<SessionDto> {
+  async fn describe_session((session_id, created_at): (String, SystemTime)) -> SessionDto {
+    let tabs = tab::api::session_tabs(session_id.as_str()).await;
+    SessionDto {
+      session_id,
+      created_at: to_unix_millis(created_at),
+      tabs,
+    }
+  }
+
+  let snapshot = SESSIONS
+    .lock()
+    .await
+    .iter()
+    .map(|(session_id, session)| (session_id.clone(), session.created_at))
+    .collect::<Vec<_>>();
+
+  future::join_all(snapshot.into_iter().map(describe_session)).await
+}
+
+/// Closes the session with the specified ID.
+///
+/// # Behavior
+///
+/// - Removes the session from the in-memory store, so no new tabs can join it.
+/// - Closes every tab of the session through the regular tab close path.
+/// - Disposes the session's browser context with all its cookies and storage.
+///
+/// # Arguments
+///
+/// - `session_id`: The ID of the session to close.
+///
+/// # Errors
+///
+/// Returns an `Error` if:
+/// - The session with the given ID does not exist.
+/// - Closing one of its tabs fails (the context is disposed regardless).
+/// - Disposing the browser context fails.
+///
+/// # Examples
+///
+/// ```ignore
+/// api::close(session_id).await?;
+/// ```
+pub async fn close(session_id: &str) -> Result<(), Error> {
+  async fn remove_session(session_id: &str) -> Result<(String, Session), Error> {
+    SESSIONS
+      .lock()
+      .await
+      .remove(session_id)
+      .map(|session| (session_id.to_string(), session))
+      .ok_or_else(|| Error::NotFound(format!("session_id {session_id}")))
+  }
+  async fn close_tabs(
+    (session_id, session): (String, Session),
+  ) -> Result<(Session, Option<Error>), Error> {
+    let tab_ids = tab::api::session_tabs(session_id.as_str()).await;
+    let closed = future::join_all(tab_ids.iter().map(|tab_id| tab::api::close(tab_id))).await;
+
+    // Tabs closed concurrently (e.g. by the reaper) are already gone and not an error.
+    let tab_error = closed
+      .into_iter()
+      .filter_map(Result::err)
+      .find(|e| !matches!(e, Error::NotFound(_)));
+
+    Ok((session, tab_error))
+  }
+  async fn dispose_context((session, tab_error): (Session, Option<Error>)) -> Result<(), Error> {
+    session
+      .browser
+      .dispose_browser_context(session.context_id)
+      .await
+      .map_err(|e| {
+        Error::Operation(ErrorInfo {
+          message: format!("Failed to dispose browser context: {e}"),
+          code: None,
+        })
+      })
+      .and(tab_error.map_or(Ok(()), Err))
+  }
+
+  remove_session(session_id)
+    .and_then(close_tabs)
+    .and_then(dispose_context)
+    .await
+}
+
+/// Closes every session with its tabs and browser context.
+///
+/// Intended to be called on shutdown so no browser context outlives the server.
+///
+/// # Examples
+///
+/// ```ignore
+/// api::drain().await;
+/// ```
+pub async fn drain() {
+  async fn close_session(session_id: String) {
+    if let Err(e) = close(&session_id).await {
+      tracing::warn!("Failed to close session {session_id} on shutdown: {e}");
+    }
+  }
+
+  let session_ids = SESSIONS.lock().await.keys().cloned().collect::<Vec<_>>();
+
+  tracing::info!("Closing {} sessions on shutdown", session_ids.len());
+
+  future::join_all(session_ids.into_iter().map(close_session)).await;
+}
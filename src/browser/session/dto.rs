@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+use crate::browser::tab::dto::ProxyDto;
+
+#[derive(Deserialize, Default)]
+pub struct CreateSessionDto {
+  /// Proxy every tab of the session egresses through.
+  pub proxy: Option<ProxyDto>,
+}
+
+#[derive(Serialize)]
+pub struct SessionDto {
+  pub session_id: String,
+  pub created_at: u64,
+  pub tabs: Vec<String>,
+}
@@ -0,0 +1,14 @@
+use chaser_oxide::Browser;
+use chaser_oxide::cdp::browser_protocol::browser::BrowserContextId;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use crate::browser::tab::dto::ProxyDto;
+
+/// A named browser context whose tabs share cookies, storage and cache.
+pub struct Session {
+  pub context_id: BrowserContextId,
+  pub browser: Arc<Browser>,
+  pub created_at: SystemTime,
+  pub proxy: Option<ProxyDto>,
+}
@@ -1,24 +1,70 @@
-use chaser_oxide::cdp::browser_protocol::network::{Cookie, DeleteCookiesParams};
+use chaser_oxide::auth::Credentials;
+use chaser_oxide::cdp::browser_protocol::browser::{
+  BrowserContextId, GrantPermissionsParams, PermissionType,
+};
+use chaser_oxide::cdp::browser_protocol::dom_storage::{GetDomStorageItemsParams, StorageId};
+use chaser_oxide::cdp::browser_protocol::emulation::{
+  SetDeviceMetricsOverrideParams, SetGeolocationOverrideParams, SetLocaleOverrideParams,
+  SetTimezoneOverrideParams, SetTouchEmulationEnabledParams, SetUserAgentOverrideParams,
+};
+use chaser_oxide::cdp::browser_protocol::network::{
+  Cookie, CookieParam, CookieSameSite, DeleteCookiesParams, EventRequestWillBeSent,
+  EventResponseReceived, GetCookiesParams, Headers, LoaderId, RequestId, Response,
+  SetCookiesParams, SetExtraHttpHeadersParams, TimeSinceEpoch,
+};
+use chaser_oxide::cdp::browser_protocol::page::{
+  AddScriptToEvaluateOnNewDocumentParams, EventLifecycleEvent, FrameId, FrameTree,
+  GetFrameTreeParams, GetNavigationHistoryParams, HandleJavaScriptDialogParams, NavigateParams,
+  NavigateToHistoryEntryParams, ReloadParams,
+};
+use chaser_oxide::cdp::browser_protocol::storage;
+use chaser_oxide::cdp::browser_protocol::target::{CloseTargetParams, CreateTargetParams};
+use chaser_oxide::error::CdpError;
+use chaser_oxide::handler::http::HttpRequest;
+use chaser_oxide::listeners::EventStream;
 use chaser_oxide::page::ScreenshotParams;
-use chaser_oxide::{Browser, ChaserPage, ChaserProfile, Element};
+use chaser_oxide::{
+  ArcHttpRequest, Browser, ChaserPage, ChaserProfile, ChaserProfileBuilder, Element, Page,
+};
 use futures::TryFutureExt;
 use futures::future;
-use futures::stream::{self, TryStreamExt};
+use futures::stream::{self, StreamExt, TryStreamExt};
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::sync::LazyLock;
-use tokio::sync::Mutex;
+use std::time::SystemTime;
+use tokio::sync::{Mutex, OwnedSemaphorePermit};
 use tokio::time::{Duration, Instant, sleep, timeout};
 use url::Url;
 use uuid::Uuid;
 
-use crate::browser::tab::dto::{ClickDto, ExecuteDto, ExistsDto, ExtractDto, FillDto, OpenDto};
+use crate::browser::session;
+use crate::browser::tab::dialogs::{DialogHandler, DialogWatch};
+use crate::browser::tab::dto::{
+  AnswerDialogDto, BlockDto, ClickDto, ConsoleMessageDto, ConsoleQueryDto, CookieDto,
+  CustomProfileDto, DeleteCookiesDto, DeleteRoutesDto, DeviceDto, DialogPolicyDto, DialogsDto,
+  ExecuteDto, ExistsDto, ExpirationDto, ExtractDto, FillDto, HarDto, HarOptionsDto, HeadersDto,
+  HttpCredentialsDto, NavigateDto, NavigationDto, NetworkResponseDto, NetworkRulesDto, OpenDto,
+  OriginStateDto, OsDto, PageErrorsDto, ProfileDto, ProxyDto, ProxyPoolDto, RedirectDto, RegionDto,
+  RouteAddedDto, RouteDto, SetCookiesDto, StorageCookieDto, StorageItemDto, StorageStateDto,
+  TabDto, TabExpirationDto, TabOpenedDto, ViewportDto, WaitForResponseDto, WaitUntilDto,
+  bounded_timeout, default_timeout_ms,
+};
+use crate::browser::tab::errors::ErrorRecorder;
+use crate::browser::tab::har::HarRecorder;
+use crate::browser::tab::models::{
+  ContextKind, Navigation, StealthDefaults, Tab, TabContext, TabEmulation, TabFeatures,
+  to_unix_millis,
+};
+use crate::browser::tab::network::{BlockRules, Interception, ResponseWaiter, Route};
+use crate::browser::tab::proxies::ProxyLease;
+use crate::browser::tab::{console, dialogs, errors, har, network, proxies, reaper, slots};
 use crate::models::{Error, ErrorInfo};
 
-static TABS: LazyLock<Mutex<HashMap<String, Arc<ChaserPage>>>> =
-  LazyLock::new(|| Mutex::new(HashMap::new()));
+static TABS: LazyLock<Mutex<HashMap<String, Tab>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+static STEALTH: LazyLock<StealthDefaults> = LazyLock::new(StealthDefaults::from_env);
 
-/// Finds a tab by its ID.
+/// Finds a tab by its ID and records the lookup as tab activity.
 ///
 /// # Arguments
 ///
@@ -26,7 +72,9 @@ static TABS: LazyLock<Mutex<HashMap<String, Arc<ChaserPage>>>> =
 ///
 /// # Errors
 ///
-/// Returns `Error::NotFound` if the tab with the given ID does not exist.
+/// Returns an `Error` if:
+/// - The tab with the given ID does not exist (`Error::NotFound`).
+/// - The renderer of the tab crashed (`Error::Crashed`).
 ///
 /// # Examples
 ///
@@ -34,127 +82,644 @@ static TABS: LazyLock<Mutex<HashMap<String, Arc<ChaserPage>>>> =
 /// let page = api::find(tab_id).await?;
 /// ```
 pub async fn find(tab_id: &str) -> Result<Arc<ChaserPage>, Error> {
+  live_tab(&mut *TABS.lock().await, tab_id).map(|tab| tab.page.clone())
+}
+
+/// Returns the moment the tab with the given ID is due to be closed, if it is still open.
+pub(crate) async fn due_at(tab_id: &str) -> Option<SystemTime> {
+  TABS.lock().await.get(tab_id).map(Tab::due_at)
+}
+
+/// Returns the IDs of the open tabs that belong to the given session.
+pub(crate) async fn session_tabs(session_id: &str) -> Vec<String> {
   TABS
     .lock()
     .await
-    .get(tab_id)
-    .cloned()
-    .ok_or_else(|| Error::NotFound(format!("tab_id {tab_id}")))
+    .iter()
+    .filter(|(_, tab)| {
+      matches!(&tab.context, TabContext::Session { session_id: id, .. } if id == session_id)
+    })
+    .map(|(tab_id, _)| tab_id.clone())
+    .collect()
+}
+
+/// Lists all open tabs with their metadata.
+///
+/// # Behavior
+///
+/// - Takes a snapshot of the in-memory store.
+/// - Reads the current URL and title of every tab concurrently (best-effort).
+/// - Does not count as activity for the listed tabs.
+/// - Yields `None` for the URL or title of a tab that cannot be read (e.g. while closing).
+///
+/// # Examples
+///
+/// ```ignore
+/// let tabs = api::list().await;
+/// ```
+pub async fn list() -> Vec<TabDto> {
+  async fn describe_tab((page, tab): (Arc<ChaserPage>, TabDto)) -> TabDto {
+    let (url, title) = future::join(page.raw_page().url(), page.raw_page().get_title()).await;
+    TabDto {
+      url: url.ok().flatten(),
+      title: title.ok().flatten(),
+      ..tab
+    }
+  }
+
+  let snapshot = TABS
+    .lock()
+    .await
+    .iter()
+    .map(|(tab_id, tab)| {
+      (
+        tab.page.clone(),
+        TabDto {
+          tab_id: tab_id.clone(),
+          url: None,
+          title: None,
+          created_at: to_unix_millis(tab.created_at),
+          expires_at: to_unix_millis(tab.expires_at),
+          last_activity_at: to_unix_millis(tab.last_activity),
+          idle_timeout: tab.idle_timeout.map(|idle| idle.as_secs()),
+          isolated: matches!(tab.context, TabContext::Isolated { .. }),
+          session_id: match &tab.context {
+            TabContext::Session { session_id, .. } => Some(session_id.clone()),
+            _ => None,
+          },
+          crashed: tab.is_crashed(),
+        },
+      )
+    })
+    .collect::<Vec<_>>();
+
+  future::join_all(snapshot.into_iter().map(describe_tab)).await
 }
 
 /// Opens a new tab with the specified URL and applies anti-detection measures.
 ///
 /// # Behavior
 ///
+/// - Takes a slot from the `MAX_TABS` limit, rejecting or queueing the request when none is free.
+/// - Creates a dedicated incognito browser context when `dto.isolated`, `dto.proxy` or
+///   `dto.proxy_pool` is set, or joins the browser context of the session `dto.session_id`; a proxy applies to
+///   the whole context, and its credentials are answered to `Fetch.authRequired` challenges.
+/// - Draws the proxy from the `PROXY_POOL_FILE` pool when `dto.proxy_pool` is set, once the
+///   request is validated and holds a tab slot; a failed navigation counts against the
+///   drawn proxy's health.
 /// - Creates a new page and wraps it in `ChaserPage`.
-/// - Applies the Windows stealth profile before navigation.
-/// - Navigates to the requested URL.
-/// - Schedules automatic tab closure after `dto.expiration` seconds.
+/// - Answers the auth challenges of websites with `dto.http_credentials` through `Fetch`
+///   request interception, and sends `dto.headers` with every request of the tab.
+/// - Blocks requests matching `dto.block` (resource types and URL patterns) through `Fetch`
+///   request interception for the lifetime of the tab.
+/// - Records the network traffic of the tab as HAR when `dto.har` is set.
+/// - Captures the console messages of the tab when `dto.console` is set.
+/// - Watches the tab for renderer crashes and, with `dto.errors` or `dto.console`, uncaught
+///   exceptions.
+/// - Answers JavaScript dialogs according to `dto.dialog`, or keeps them pending until
+///   answered through `answer_dialog` (the default); page actions such as `click` and
+///   `execute` fail with `Error::Blocked` meanwhile.
+/// - Applies the `dto.profile` stealth profile (an OS preset or custom fields), or the
+///   server default from `STEALTH_PROFILE`, before navigation.
+/// - Overrides the locale, timezone, geolocation and Accept-Language of the page from
+///   `dto.region`; `navigator.languages` follows the Accept-Language value. Geolocation
+///   is only available to tabs with a browser context of their own or of a session.
+/// - Emulates `dto.viewport` or the `dto.device` preset when provided; otherwise the page
+///   uses the browser window size.
+/// - Preloads `dto.storage_state` cookies and localStorage when provided.
+/// - Navigates to the requested URL with the optional `dto.referrer` and waits until
+///   `dto.wait_until` is reached (the `load` event by default).
+/// - Closes the tab again when `dto.fail_on_status` is set and the main document
+///   responded with 4xx/5xx.
+/// - Schedules automatic tab closure with the reaper after `dto.expiration` seconds,
+///   or earlier after `dto.idle_timeout` seconds without activity when provided.
+/// - Returns the tab ID, the expiration deadline and the navigation result: final URL,
+///   title, status and headers of the main document and the redirect chain.
 ///
 /// # Arguments
 ///
 /// - `browser`: The shared browser instance.
-/// - `dto`: Open payload including the URL and expiration.
+/// - `dto`: Open payload including the URL, expiration and optional idle timeout.
 ///
 /// # Errors
 ///
 /// Returns an `Error` if:
+/// - `session_id` is combined with `isolated`, `proxy` or `proxy_pool`, `proxy` with
+///   `proxy_pool`, or `viewport` with `device`.
+/// - `geolocation` is set for a tab in the shared profile (neither isolated nor in a session).
+/// - The blocking rules are invalid (see `BlockRules::compile`).
+/// - The proxy is invalid (see `network::context_params`), or none can be drawn from the
+///   pool (see `proxies::draw`).
 /// - The URL is invalid.
-/// - Creating a new tab fails.
-/// - Applying the stealth profile fails.
+/// - The session does not exist, or is closed while the tab is opening.
+/// - No tab slot is available (`Error::Exhausted`).
+/// - Creating the browser context or a new tab fails.
+/// - Starting the request interception, setting the extra headers, starting the HAR
+///   recording, the console capture, the error watch or the dialog handling fails.
+/// - Applying the stealth profile, the regional overrides or the viewport fails.
 /// - Navigation to the URL fails.
+/// - Navigation does not finish within `dto.timeout_ms` (`Error::Timeout`).
+/// - `dto.fail_on_status` is set and the main document responded with 4xx/5xx; the
+///   status is reported as the error code.
 ///
 /// # Examples
 ///
 /// ```ignore
-/// let tab_id = api::open(browser, OpenDto { url: "https://example.com".into(), expiration: 60 }).await?;
+/// let opened = api::open(
+///   browser,
+///   OpenDto { url: "https://example.com".into(), expiration: 60, idle_timeout: Some(10) },
+/// )
+/// .await?;
 /// ```
-pub async fn open(browser: Arc<Browser>, dto: OpenDto) -> Result<String, Error> {
-  fn schedule_auto_close(tab_id: String, expiration: u64) {
-    tokio::spawn(async move {
-      tokio::time::sleep(Duration::from_secs(expiration)).await;
-      match close(&tab_id).await {
-        Ok(()) => tracing::info!("Tab {tab_id} expired after {expiration} seconds"),
-        Err(e) => tracing::warn!("Failed to auto-close tab {tab_id} after expiration: {e}"),
-      }
-    });
+pub async fn open(browser: Arc<Browser>, mut dto: OpenDto) -> Result<TabOpenedDto, Error> {
+  async fn acquire_slot(
+    (url, browser, kind): (Url, Arc<Browser>, ContextKind),
+  ) -> Result<(Url, Arc<Browser>, Option<OwnedSemaphorePermit>, ContextKind), Error> {
+    slots::acquire()
+      .await
+      .map(|slot| (url, browser, slot, kind))
   }
   #[inline]
-  fn parse_url(url: &str) -> Result<Url, Error> {
-    Url::parse(url).map_err(|e| {
-      Error::Operation(ErrorInfo {
-        message: format!("Invalid URL: {e}"),
-        code: None,
-      })
-    })
+  fn select_context(dto: &OpenDto, pool: Option<ProxyPoolDto>) -> Result<ContextKind, Error> {
+    match (
+      dto.isolated,
+      dto.session_id.clone(),
+      dto.proxy.clone(),
+      pool,
+    ) {
+      (_, _, Some(_), Some(_)) => Err(Error::NotSupported(
+        "proxy and proxy_pool cannot be combined".to_string(),
+      )),
+      (false, None, None, None) => Ok(ContextKind::Shared),
+      (_, None, proxy, pool) => Ok(ContextKind::Isolated { proxy, pool }),
+      (false, Some(session_id), None, None) => Ok(ContextKind::Session(session_id)),
+      (true, Some(_), _, _) => Err(Error::NotSupported(
+        "isolated and session_id cannot be combined".to_string(),
+      )),
+      (false, Some(_), _, _) => Err(Error::NotSupported(
+        "proxy and proxy_pool cannot be combined with session_id; set the proxy on the session"
+          .to_string(),
+      )),
+    }
+  }
+  #[inline]
+  fn check_region(kind: ContextKind, region: &RegionDto) -> Result<ContextKind, Error> {
+    // The geolocation permission is granted for the whole browser context, which in the
+    // shared profile would outlive the tab and reach every other shared tab.
+    match kind {
+      ContextKind::Shared if region.geolocation.is_some() => Err(Error::NotSupported(
+        "geolocation needs an isolated tab or a session".to_string(),
+      )),
+      kind => Ok(kind),
+    }
+  }
+  #[inline]
+  fn select_viewport(
+    viewport: Option<ViewportDto>,
+    device: Option<DeviceDto>,
+  ) -> Result<Option<ViewportDto>, Error> {
+    match (viewport, device) {
+      (Some(_), Some(_)) => Err(Error::NotSupported(
+        "viewport and device cannot be combined".to_string(),
+      )),
+      (viewport, device) => Ok(viewport.or_else(|| device.map(|device| device.viewport()))),
+    }
+  }
+  async fn create_context(
+    (url, browser, slot, kind): (Url, Arc<Browser>, Option<OwnedSemaphorePermit>, ContextKind),
+  ) -> Result<
+    (
+      Url,
+      Arc<Browser>,
+      Option<OwnedSemaphorePermit>,
+      TabContext,
+      Option<ProxyDto>,
+      Option<ProxyLease>,
+    ),
+    Error,
+  > {
+    match kind {
+      ContextKind::Shared => Ok((url, browser, slot, TabContext::Shared, None, None)),
+      ContextKind::Session(session_id) => {
+        session::api::find(session_id.as_str())
+          .await
+          .map(|(id, proxy)| {
+            (
+              url,
+              browser,
+              slot,
+              TabContext::Session { session_id, id },
+              proxy,
+              None,
+            )
+          })
+      }
+      ContextKind::Isolated { proxy, pool } => {
+        // Drawn only now, so requests rejected earlier do not count against the pool.
+        let (proxy, lease) = match pool {
+          Some(request) => {
+            proxies::draw(&request).map(|(proxy, lease)| (Some(proxy), Some(lease)))?
+          }
+          None => (proxy, None),
+        };
+        let params = network::context_params(proxy.as_ref())?;
+        browser
+          .create_browser_context(params)
+          .await
+          .map(|id| {
+            let context = TabContext::Isolated {
+              id,
+              browser: browser.clone(),
+            };
+            (url, browser, slot, context, proxy, lease)
+          })
+          .map_err(|e| {
+            Error::Operation(ErrorInfo {
+              message: format!("Failed to create browser context: {e}"),
+              code: None,
+            })
+          })
+      }
+    }
   }
   async fn create_new_tab(
-    (url, browser): (Url, Arc<Browser>),
-  ) -> Result<(Arc<ChaserPage>, Url), Error> {
-    let page = browser
-      .new_page("about:blank")
-      .map_err(|e| {
-        Error::Operation(ErrorInfo {
-          message: format!("Failed to create new page: {e}"),
-          code: None,
-        })
-      })
-      .await?;
-
-    let chaser = Arc::new(ChaserPage::new(page));
-    let profile = ChaserProfile::linux().build();
+    (url, browser, slot, context, proxy, proxy_lease): (
+      Url,
+      Arc<Browser>,
+      Option<OwnedSemaphorePermit>,
+      TabContext,
+      Option<ProxyDto>,
+      Option<ProxyLease>,
+    ),
+  ) -> Result<(Tab, Url, Option<Credentials>), Error> {
+    let params = CreateTargetParams {
+      browser_context_id: context.id(),
+      ..CreateTargetParams::new("about:blank")
+    };
 
-    match chaser.apply_profile(&profile).await {
-      Ok(()) => Ok((chaser, url)),
+    match browser.new_page(params).await {
+      Ok(page) => {
+        let chaser = Arc::new(ChaserPage::new(page));
+        let credentials = proxy.as_ref().and_then(ProxyDto::credentials);
+        let tab = Tab {
+          proxy_lease,
+          ..Tab::new(chaser, context, slot)
+        };
+        Ok((tab, url, credentials))
+      }
       Err(e) => {
-        close_page(chaser).await?;
+        if let Err(e) = dispose_context(context).await {
+          tracing::warn!("Failed to dispose the context of a tab that failed to open: {e}");
+        }
         Err(Error::Operation(ErrorInfo {
-          message: format!("Failed to apply stealth profile: {e}"),
+          message: format!("Failed to create new page: {e}"),
           code: None,
         }))
       }
     }
   }
+  async fn intercept_requests(
+    (tab, url, proxy_credentials, http_credentials, block): (
+      Tab,
+      Url,
+      Option<Credentials>,
+      Option<Credentials>,
+      Option<BlockRules>,
+    ),
+  ) -> Result<(Tab, Url), Error> {
+    if proxy_credentials.is_none() && http_credentials.is_none() && block.is_none() {
+      return Ok((tab, url));
+    }
+
+    let interception = Interception::new(
+      proxy_credentials,
+      http_credentials,
+      block.unwrap_or_default(),
+    );
+    let intercepted = network::intercept(tab.page.raw_page(), interception).await;
+    discard_on_error(tab, intercepted)
+      .await
+      .map(|(tab, interceptor)| {
+        let tab = Tab {
+          interceptor: Some(interceptor),
+          ..tab
+        };
+        (tab, url)
+      })
+  }
+  async fn apply_headers(
+    (tab, url, headers): (Tab, Url, HashMap<String, String>),
+  ) -> Result<(Tab, Url), Error> {
+    if headers.is_empty() {
+      return Ok((tab, url));
+    }
+
+    let applied = set_extra_headers(&tab.page, headers).await;
+    discard_on_error(tab, applied)
+      .await
+      .map(|(tab, ())| (tab, url))
+  }
+  async fn record_traffic(
+    (tab, url, options): (Tab, Url, Option<HarOptionsDto>),
+  ) -> Result<(Tab, Url), Error> {
+    let Some(options) = options else {
+      return Ok((tab, url));
+    };
+
+    let recording = har::record(tab.page.raw_page(), options).await;
+    discard_on_error(tab, recording)
+      .await
+      .map(|(tab, recorder)| {
+        let tab = Tab {
+          har: Some(recorder),
+          ..tab
+        };
+        (tab, url)
+      })
+  }
+  async fn capture_console((tab, url, console): (Tab, Url, bool)) -> Result<(Tab, Url), Error> {
+    if !console {
+      return Ok((tab, url));
+    }
+
+    let recording = console::record(tab.page.raw_page()).await;
+    discard_on_error(tab, recording)
+      .await
+      .map(|(tab, recorder)| {
+        let tab = Tab {
+          console: Some(recorder),
+          ..tab
+        };
+        (tab, url)
+      })
+  }
+  async fn watch_errors((tab, url, exceptions): (Tab, Url, bool)) -> Result<(Tab, Url), Error> {
+    let watching = errors::watch(tab.page.raw_page(), exceptions).await;
+    discard_on_error(tab, watching)
+      .await
+      .map(|(tab, recorder)| {
+        let tab = Tab {
+          errors: Some(recorder),
+          ..tab
+        };
+        (tab, url)
+      })
+  }
+  async fn handle_dialogs(
+    (tab, url, policy): (Tab, Url, DialogPolicyDto),
+  ) -> Result<(Tab, Url), Error> {
+    let handling = dialogs::handle(tab.page.raw_page(), policy).await;
+    discard_on_error(tab, handling).await.map(|(tab, handler)| {
+      let tab = Tab {
+        dialogs: Some(handler),
+        ..tab
+      };
+      (tab, url)
+    })
+  }
+  async fn apply_stealth_profile(
+    (tab, url, profile, region): (Tab, Url, Option<ProfileDto>, RegionDto),
+  ) -> Result<(Tab, Url), Error> {
+    let applied = match apply_profile(&tab.page, profile, &region).await {
+      Ok(()) => emulate_region(&tab.page, tab.context.id(), &region).await,
+      Err(e) => Err(e),
+    };
+
+    discard_on_error(tab, applied)
+      .await
+      .map(|(tab, ())| (tab, url))
+  }
+  async fn apply_viewport(
+    (tab, url, viewport): (Tab, Url, Option<ViewportDto>),
+  ) -> Result<(Tab, Url), Error> {
+    let Some(viewport) = viewport else {
+      return Ok((tab, url));
+    };
+
+    let applied = emulate_viewport(&tab.page, &viewport).await;
+    discard_on_error(tab, applied)
+      .await
+      .map(|(tab, ())| (tab, url))
+  }
+  async fn preload_storage_state(
+    (tab, url, state): (Tab, Url, Option<StorageStateDto>),
+  ) -> Result<(Tab, Url), Error> {
+    let Some(state) = state else {
+      return Ok((tab, url));
+    };
+
+    let loaded = load_storage_state(&tab.page, state).await;
+    discard_on_error(tab, loaded)
+      .await
+      .map(|(tab, ())| (tab, url))
+  }
   async fn navigate_to_url(
-    (chaser, url): (Arc<ChaserPage>, Url),
-  ) -> Result<Arc<ChaserPage>, Error> {
-    match chaser.goto(url.as_str()).await {
-      Ok(()) => Ok(chaser),
-      Err(e) => {
-        close_page(chaser).await?;
-        Err(Error::Operation(ErrorInfo {
-          message: format!("Failed to navigate to URL: {e}"),
-          code: None,
-        }))
-      }
+    (tab, navigation, wait_until, limit): (Tab, Navigation, WaitUntilDto, Duration),
+  ) -> Result<(Tab, ArcHttpRequest), Error> {
+    let loaded = load_page(&tab.page, navigation, &wait_until, limit).await;
+    if let Some(lease) = &tab.proxy_lease {
+      proxies::report(lease, loaded.is_ok());
     }
+
+    discard_on_error(tab, loaded).await
+  }
+  async fn check_status(
+    (tab, request, fail_on_status): (Tab, ArcHttpRequest, bool),
+  ) -> Result<(Tab, NavigationDto), Error> {
+    let navigation = describe_navigation(&tab.page, request).await;
+    let checked = match navigation.status {
+      Some(status) if fail_on_status && status >= 400 => Err(Error::Operation(ErrorInfo {
+        message: format!("Main document responded with HTTP {status}"),
+        code: Some(status.to_string()),
+      })),
+      _ => Ok(navigation),
+    };
+
+    discard_on_error(tab, checked).await
   }
-  async fn store_tab(page: Arc<ChaserPage>) -> Result<String, Error> {
+  async fn store_tab(
+    (tab, navigation, expiration, idle_timeout): (Tab, NavigationDto, u64, Option<u64>),
+  ) -> Result<TabOpenedDto, Error> {
     let tab_id = Uuid::new_v4().to_string();
-    let mut tabs = TABS.lock().await;
-    tabs.insert(tab_id.clone(), page);
-    Ok(tab_id)
-  }
-
-  future::ready(parse_url(dto.url.as_str()))
-    .map_ok(move |url| (url, browser))
-    .and_then(create_new_tab)
-    .and_then(navigate_to_url)
-    .and_then(store_tab)
-    .map_ok(|tab_id| {
-      schedule_auto_close(tab_id.clone(), dto.bounded_expiration());
-      tab_id
+    let tab = tab.with_expiration(expiration, idle_timeout);
+    let expires_at = to_unix_millis(tab.expires_at);
+    let due_at = tab.due_at();
+
+    // A session closed while the tab was opening must not get it after collecting its tabs.
+    let held = match &tab.context {
+      TabContext::Session { session_id, .. } => session::api::hold_open(session_id).await.map(Some),
+      TabContext::Shared | TabContext::Isolated { .. } => Ok(None),
+    };
+    let (tab, session) = discard_on_error(tab, held).await?;
+    TABS.lock().await.insert(tab_id.clone(), tab);
+    drop(session);
+
+    reaper::schedule(&tab_id, due_at).await;
+    Ok(TabOpenedDto {
+      tab_id,
+      expires_at,
+      navigation,
     })
-    .await
+  }
+
+  /// Creates the page of the tab in the requested browser context once a slot is free.
+  async fn create_tab(
+    (url, browser, kind): (Url, Arc<Browser>, ContextKind),
+  ) -> Result<(Tab, Url, Option<Credentials>), Error> {
+    acquire_slot((url, browser, kind))
+      .and_then(create_context)
+      .and_then(create_new_tab)
+      .await
+  }
+  /// Sets up the request interception, recordings and handlers of the tab.
+  async fn set_up_features(
+    (tab, url, proxy_credentials, features): (Tab, Url, Option<Credentials>, TabFeatures),
+  ) -> Result<(Tab, Url), Error> {
+    let TabFeatures {
+      http_credentials,
+      block,
+      headers,
+      har,
+      console,
+      exceptions,
+      dialog,
+    } = features;
+
+    intercept_requests((tab, url, proxy_credentials, http_credentials, block))
+      .map_ok(move |(tab, url)| (tab, url, headers))
+      .and_then(apply_headers)
+      .map_ok(move |(tab, url)| (tab, url, har))
+      .and_then(record_traffic)
+      .map_ok(move |(tab, url)| (tab, url, console))
+      .and_then(capture_console)
+      .map_ok(move |(tab, url)| (tab, url, exceptions))
+      .and_then(watch_errors)
+      .map_ok(move |(tab, url)| (tab, url, dialog))
+      .and_then(handle_dialogs)
+      .await
+  }
+  /// Applies the stealth profile, regional overrides, viewport and storage state.
+  async fn emulate((tab, url, emulation): (Tab, Url, TabEmulation)) -> Result<(Tab, Url), Error> {
+    let TabEmulation {
+      profile,
+      region,
+      viewport,
+      storage_state,
+    } = emulation;
+
+    apply_stealth_profile((tab, url, profile, region))
+      .map_ok(move |(tab, url)| (tab, url, viewport))
+      .and_then(apply_viewport)
+      .map_ok(move |(tab, url)| (tab, url, storage_state))
+      .and_then(preload_storage_state)
+      .await
+  }
+  /// Navigates the tab and checks the status of the main document.
+  async fn load(
+    (tab, navigation, wait_until, limit, fail_on_status): (
+      Tab,
+      Navigation,
+      WaitUntilDto,
+      Duration,
+      bool,
+    ),
+  ) -> Result<(Tab, NavigationDto), Error> {
+    navigate_to_url((tab, navigation, wait_until, limit))
+      .map_ok(move |(tab, request)| (tab, request, fail_on_status))
+      .and_then(check_status)
+      .await
+  }
+
+  let expiration = dto.bounded_expiration();
+  let idle_timeout = dto.bounded_idle_timeout();
+  let referrer = dto.referrer.take();
+  let wait_until = std::mem::take(&mut dto.wait_until);
+  let limit = bounded_timeout(dto.timeout_ms);
+  let fail_on_status = dto.fail_on_status;
+  let emulation = TabEmulation {
+    viewport: select_viewport(dto.viewport.take(), dto.device.take())?,
+    profile: dto.profile.take(),
+    region: std::mem::take(&mut dto.region),
+    storage_state: dto.storage_state.take(),
+  };
+  let proxy_pool = dto.proxy_pool.take();
+  let features = TabFeatures {
+    block: dto.block.take().map(BlockRules::compile).transpose()?,
+    har: dto.har.take(),
+    console: dto.console,
+    exceptions: dto.errors || dto.console,
+    dialog: std::mem::take(&mut dto.dialog),
+    headers: std::mem::take(&mut dto.headers),
+    http_credentials: dto.http_credentials.take().map(Credentials::from),
+  };
+
+  future::ready(
+    select_context(&dto, proxy_pool)
+      .and_then(|kind| check_region(kind, &emulation.region))
+      .and_then(|kind| parse_url(dto.url.as_str()).map(|url| (url, browser, kind))),
+  )
+  .and_then(create_tab)
+  .map_ok(move |(tab, url, credentials)| (tab, url, credentials, features))
+  .and_then(set_up_features)
+  .map_ok(move |(tab, url)| (tab, url, emulation))
+  .and_then(emulate)
+  .map_ok(move |(tab, url)| {
+    let navigation = Navigation::Goto { url, referrer };
+    (tab, navigation, wait_until, limit, fail_on_status)
+  })
+  .and_then(load)
+  .map_ok(move |(tab, navigation)| (tab, navigation, expiration, idle_timeout))
+  .and_then(store_tab)
+  .await
+}
+
+/// Updates the expiration deadline of the tab with the specified ID.
+///
+/// # Behavior
+///
+/// - Extends the current deadline or resets it relative to now, clamped to 1..=3600 seconds.
+/// - Reschedules the pending auto-close of the tab with the reaper.
+/// - Counts as activity for the tab.
+///
+/// # Arguments
+///
+/// - `tab_id`: The ID of the tab to update.
+/// - `dto`: Expiration payload.
+///
+/// # Errors
+///
+/// Returns an `Error` if:
+/// - The tab with the given ID does not exist.
+/// - The renderer of the tab crashed (`Error::Crashed`).
+///
+/// # Examples
+///
+/// ```ignore
+/// let expiration = api::reschedule(tab_id, ExpirationDto::Extend(60)).await?;
+/// ```
+pub async fn reschedule(tab_id: &str, dto: ExpirationDto) -> Result<TabExpirationDto, Error> {
+  let now = SystemTime::now();
+
+  let (expires_at, due_at) = live_tab(&mut *TABS.lock().await, tab_id).map(|tab| {
+    tab.expires_at = dto.deadline(tab.expires_at, now);
+    (tab.expires_at, tab.due_at())
+  })?;
+
+  reaper::schedule(tab_id, due_at).await;
+
+  Ok(TabExpirationDto {
+    tab_id: tab_id.to_string(),
+    expires_at: to_unix_millis(expires_at),
+  })
 }
 
 /// Closes the tab with the specified ID.
 ///
 /// # Behavior
 ///
-/// - Removes the tab from the in-memory store.
-/// - Clears cookies for the tab's current URL.
-/// - Closes the underlying page.
+/// - Removes the tab from the in-memory store and cancels its pending expiration.
+/// - Clears cookies for the tab's current URL (shared profile only).
+/// - Closes the underlying page and disposes the tab's own browser context, if any.
 ///
 /// # Arguments
 ///
@@ -166,6 +731,7 @@ pub async fn open(browser: Arc<Browser>, dto: OpenDto) -> Result<String, Error>
 /// - The tab with the given ID does not exist.
 /// - Reading or deleting cookies fails.
 /// - Closing the tab fails.
+/// - Disposing the browser context fails.
 ///
 /// # Examples
 ///
@@ -173,23 +739,28 @@ pub async fn open(browser: Arc<Browser>, dto: OpenDto) -> Result<String, Error>
 /// api::close(tab_id).await?;
 /// ```
 pub async fn close(tab_id: &str) -> Result<(), Error> {
-  async fn remove_tab(tab_id: &str) -> Result<(String, Arc<ChaserPage>), Error> {
-    TABS
-      .lock()
-      .await
-      .remove(tab_id)
-      .map(|page| (tab_id.to_string(), page))
+  async fn remove_tab(tab_id: &str) -> Result<(String, Tab), Error> {
+    let tab = TABS.lock().await.remove(tab_id);
+    reaper::cancel(tab_id).await;
+    tab
+      .map(|tab| (tab_id.to_string(), tab))
       .ok_or_else(|| Error::NotFound(format!("tab_id {tab_id}")))
   }
   async fn get_cookies(
-    (tab_id, chaser): (String, Arc<ChaserPage>),
-  ) -> Result<(String, Vec<Cookie>, Arc<ChaserPage>, Option<Error>), Error> {
-    match chaser.raw_page().get_cookies().await {
-      Ok(cookies) => Ok((tab_id, cookies, chaser, None)),
+    (tab_id, tab): (String, Tab),
+  ) -> Result<(String, Vec<Cookie>, Tab, Option<Error>), Error> {
+    // Cookies of a dedicated browser context go away together with the context, and a
+    // crashed renderer cannot answer.
+    if !matches!(tab.context, TabContext::Shared) || tab.is_crashed() {
+      return Ok((tab_id, Vec::new(), tab, None));
+    }
+
+    match tab.page.raw_page().get_cookies().await {
+      Ok(cookies) => Ok((tab_id, cookies, tab, None)),
       Err(e) => Ok((
         tab_id,
         Vec::new(),
-        chaser,
+        tab,
         Some(Error::Operation(ErrorInfo {
           message: format!("Failed to get cookies: {e}"),
           code: None,
@@ -198,34 +769,24 @@ pub async fn close(tab_id: &str) -> Result<(), Error> {
     }
   }
   async fn clear_cookies(
-    (tab_id, cookies, chaser, cookie_error): (String, Vec<Cookie>, Arc<ChaserPage>, Option<Error>),
-  ) -> Result<(Arc<ChaserPage>, Option<Error>), Error> {
+    (tab_id, cookies, tab, cookie_error): (String, Vec<Cookie>, Tab, Option<Error>),
+  ) -> Result<(Tab, Option<Error>), Error> {
     if cookie_error.is_some() {
-      return Ok((chaser, cookie_error));
+      return Ok((tab, cookie_error));
     }
 
-    let to_delete = cookies
-      .iter()
-      .map(|cookie| {
-        DeleteCookiesParams::builder()
-          .name(cookie.name.clone())
-          .domain(cookie.domain.clone())
-          .path(cookie.path.clone())
-          .build()
-          .unwrap_or_else(|_| DeleteCookiesParams::new(cookie.name.clone()))
-      })
-      .collect::<Vec<_>>();
+    let to_delete = to_delete_params(&cookies);
 
     if !to_delete.is_empty() {
       let deleted_count = to_delete.len();
 
-      return match chaser.raw_page().delete_cookies(to_delete).await {
+      return match tab.page.raw_page().delete_cookies(to_delete).await {
         Ok(_) => {
           tracing::info!("Deleted {} cookies for tab {}", deleted_count, tab_id);
-          Ok((chaser, None))
+          Ok((tab, None))
         }
         Err(e) => Ok((
-          chaser,
+          tab,
           Some(Error::Operation(ErrorInfo {
             message: format!("Failed to delete cookies: {e}"),
             code: None,
@@ -234,33 +795,1013 @@ pub async fn close(tab_id: &str) -> Result<(), Error> {
       };
     }
 
-    Ok((chaser, None))
+    Ok((tab, None))
+  }
+  async fn close_tab((tab, cookie_error): (Tab, Option<Error>)) -> Result<(), Error> {
+    let crashed = tab.is_crashed();
+    let closed = match close_page(tab.page, crashed).await {
+      Ok(()) => match cookie_error {
+        Some(cookie_error) => Err(cookie_error),
+        None => Ok(()),
+      },
+      Err(close_error) => match cookie_error {
+        Some(cookie_error) => Err(Error::Operation(ErrorInfo {
+          message: format!(
+            "Failed to close tab after cookie cleanup error. cookie_error: {cookie_error}; close_error: {close_error}"
+          ),
+          code: None,
+        })),
+        None => Err(close_error),
+      },
+    };
+    let disposed = dispose_context(tab.context).await;
+
+    closed.and(disposed)
+  }
+
+  remove_tab(tab_id)
+    .and_then(get_cookies)
+    .and_then(clear_cookies)
+    .and_then(close_tab)
+    .await
+}
+
+/// Navigates the tab to the given URL.
+///
+/// # Behavior
+///
+/// - Resolves the tab by ID.
+/// - Navigates the main frame with the optional `dto.referrer`.
+/// - Waits until `dto.wait_until` is reached within `dto.timeout_ms`.
+/// - Returns the final URL, title and HTTP status of the main document.
+///
+/// # Arguments
+///
+/// - `tab_id`: The ID of the tab to navigate.
+/// - `dto`: Navigate payload with the target URL.
+///
+/// # Errors
+///
+/// Returns an `Error` if:
+/// - The URL is invalid.
+/// - The tab with the given ID does not exist.
+/// - Navigation fails.
+/// - Navigation does not finish within `dto.timeout_ms` (`Error::Timeout`).
+/// - A JavaScript dialog is pending or opens meanwhile (`Error::Blocked`).
+///
+/// # Examples
+///
+/// ```ignore
+/// let navigation = api::navigate(tab_id, NavigateDto { url: "https://example.com".into() }).await?;
+/// ```
+pub async fn navigate(tab_id: &str, dto: NavigateDto) -> Result<NavigationDto, Error> {
+  let url = parse_url(dto.url.as_str())?;
+  let navigation = Navigation::Goto {
+    url,
+    referrer: dto.referrer,
+  };
+  run_navigation(
+    tab_id,
+    navigation,
+    dto.wait_until,
+    bounded_timeout(dto.timeout_ms),
+  )
+  .await
+}
+
+/// Navigates the tab to the previous entry of its history.
+///
+/// # Errors
+///
+/// Returns an `Error` if:
+/// - The tab with the given ID does not exist.
+/// - There is no previous history entry.
+/// - Navigation fails.
+/// - A JavaScript dialog is pending or opens meanwhile (`Error::Blocked`).
+///
+/// # Examples
+///
+/// ```ignore
+/// let navigation = api::back(tab_id).await?;
+/// ```
+pub async fn back(tab_id: &str) -> Result<NavigationDto, Error> {
+  run_navigation(
+    tab_id,
+    Navigation::Back,
+    WaitUntilDto::Load,
+    bounded_timeout(default_timeout_ms()),
+  )
+  .await
+}
+
+/// Navigates the tab to the next entry of its history.
+///
+/// # Errors
+///
+/// Returns an `Error` if:
+/// - The tab with the given ID does not exist.
+/// - There is no next history entry.
+/// - Navigation fails.
+/// - A JavaScript dialog is pending or opens meanwhile (`Error::Blocked`).
+///
+/// # Examples
+///
+/// ```ignore
+/// let navigation = api::forward(tab_id).await?;
+/// ```
+pub async fn forward(tab_id: &str) -> Result<NavigationDto, Error> {
+  run_navigation(
+    tab_id,
+    Navigation::Forward,
+    WaitUntilDto::Load,
+    bounded_timeout(default_timeout_ms()),
+  )
+  .await
+}
+
+/// Reloads the current page of the tab.
+///
+/// # Errors
+///
+/// Returns an `Error` if:
+/// - The tab with the given ID does not exist.
+/// - Reloading fails.
+/// - A JavaScript dialog is pending or opens meanwhile (`Error::Blocked`).
+///
+/// # Examples
+///
+/// ```ignore
+/// let navigation = api::reload(tab_id).await?;
+/// ```
+pub async fn reload(tab_id: &str) -> Result<NavigationDto, Error> {
+  run_navigation(
+    tab_id,
+    Navigation::Reload,
+    WaitUntilDto::Load,
+    bounded_timeout(default_timeout_ms()),
+  )
+  .await
+}
+
+/// Returns the cookies visible to the tab's current URL.
+///
+/// # Behavior
+///
+/// - Resolves the tab by ID.
+/// - Reads the cookies via `Network.getCookies` and returns them as CDP `Cookie` data.
+///
+/// # Arguments
+///
+/// - `tab_id`: The ID of the tab to read cookies from.
+///
+/// # Errors
+///
+/// Returns an `Error` if:
+/// - The tab with the given ID does not exist.
+/// - Reading the cookies fails.
+///
+/// # Examples
+///
+/// ```ignore
+/// let cookies = api::get_cookies(tab_id).await?;
+/// ```
+pub async fn get_cookies(tab_id: &str) -> Result<Vec<Cookie>, Error> {
+  find(tab_id).and_then(read_cookies).await
+}
+
+/// Sets one or many cookies in the tab's browser context.
+///
+/// # Behavior
+///
+/// - Resolves the tab by ID.
+/// - Cookies without `url` and `domain` are scoped to the tab's current URL.
+/// - Sets all cookies in a single `Network.setCookies` call.
+///
+/// # Arguments
+///
+/// - `tab_id`: The ID of the tab to set cookies for.
+/// - `dto`: Cookies payload.
+///
+/// # Errors
+///
+/// Returns an `Error` if:
+/// - The tab with the given ID does not exist.
+/// - A cookie has neither `url` nor `domain` and the tab is not on an HTTP(S) page.
+/// - Setting the cookies fails.
+///
+/// # Examples
+///
+/// ```ignore
+/// api::set_cookies(tab_id, SetCookiesDto { cookies: vec![/* ... */] }).await?;
+/// ```
+pub async fn set_cookies(tab_id: &str, dto: SetCookiesDto) -> Result<(), Error> {
+  async fn resolve_page_url(
+    chaser: Arc<ChaserPage>,
+  ) -> Result<(Arc<ChaserPage>, Option<String>), Error> {
+    chaser
+      .raw_page()
+      .url()
+      .await
+      .map(|url| {
+        let http_url = url.filter(|url| url.starts_with("http"));
+        (chaser, http_url)
+      })
+      .map_err(|e| {
+        Error::Operation(ErrorInfo {
+          message: format!("Failed to read tab URL: {e}"),
+          code: None,
+        })
+      })
+  }
+  fn to_cookie_param(cookie: CookieDto, page_url: Option<&str>) -> Result<CookieParam, Error> {
+    let url = match (cookie.url, &cookie.domain) {
+      (Some(url), _) => Some(url),
+      (None, Some(_)) => None,
+      (None, None) => Some(page_url.map(str::to_string).ok_or_else(|| {
+        Error::NotSupported(format!(
+          "cookie '{}' needs a url or domain while the tab is not on an HTTP(S) page",
+          cookie.name
+        ))
+      })?),
+    };
+
+    Ok(CookieParam {
+      url,
+      domain: cookie.domain,
+      path: cookie.path,
+      secure: cookie.secure,
+      http_only: cookie.http_only,
+      same_site: cookie.same_site,
+      expires: cookie.expires.map(TimeSinceEpoch::new),
+      ..CookieParam::new(cookie.name, cookie.value)
+    })
+  }
+  async fn write_cookies(
+    ((chaser, page_url), cookies): ((Arc<ChaserPage>, Option<String>), Vec<CookieDto>),
+  ) -> Result<(), Error> {
+    let params = cookies
+      .into_iter()
+      .map(|cookie| to_cookie_param(cookie, page_url.as_deref()))
+      .collect::<Result<Vec<_>, _>>()?;
+
+    chaser
+      .raw_page()
+      .execute(SetCookiesParams::new(params))
+      .await
+      .map(|_| ())
+      .map_err(|e| {
+        Error::Operation(ErrorInfo {
+          message: format!("Failed to set cookies: {e}"),
+          code: None,
+        })
+      })
+  }
+
+  let cookies = dto.cookies;
+
+  find(tab_id)
+    .and_then(resolve_page_url)
+    .map_ok(move |resolved| (resolved, cookies))
+    .and_then(write_cookies)
+    .await
+}
+
+/// Deletes the tab's cookies matching the given filter, or all of them.
+///
+/// # Behavior
+///
+/// - Resolves the tab by ID.
+/// - Reads the cookies visible to the tab's current URL.
+/// - Deletes those matching every provided `name`, `domain` and `path` filter.
+/// - Returns the deleted cookies.
+///
+/// # Arguments
+///
+/// - `tab_id`: The ID of the tab to delete cookies from.
+/// - `dto`: Optional filters; an empty filter deletes all cookies.
+///
+/// # Errors
+///
+/// Returns an `Error` if:
+/// - The tab with the given ID does not exist.
+/// - Reading or deleting the cookies fails.
+///
+/// # Examples
+///
+/// ```ignore
+/// let deleted = api::delete_cookies(tab_id, DeleteCookiesDto { name: Some("sid".into()), domain: None, path: None }).await?;
+/// ```
+pub async fn delete_cookies(tab_id: &str, dto: DeleteCookiesDto) -> Result<Vec<Cookie>, Error> {
+  fn matches_filter(cookie: &Cookie, dto: &DeleteCookiesDto) -> bool {
+    // `.example.com` and `example.com` name the same cookie domain.
+    let same_domain =
+      |domain: &String| domain.trim_start_matches('.') == cookie.domain.trim_start_matches('.');
+
+    dto.name.as_ref().is_none_or(|name| *name == cookie.name)
+      && dto.domain.as_ref().is_none_or(same_domain)
+      && dto.path.as_ref().is_none_or(|path| *path == cookie.path)
+  }
+  async fn remove_cookies(
+    ((chaser, cookies), dto): ((Arc<ChaserPage>, Vec<Cookie>), DeleteCookiesDto),
+  ) -> Result<Vec<Cookie>, Error> {
+    let selected = cookies
+      .into_iter()
+      .filter(|cookie| matches_filter(cookie, &dto))
+      .collect::<Vec<_>>();
+
+    if selected.is_empty() {
+      return Ok(selected);
+    }
+
+    chaser
+      .raw_page()
+      .delete_cookies(to_delete_params(&selected))
+      .await
+      .map(|_| selected)
+      .map_err(|e| {
+        Error::Operation(ErrorInfo {
+          message: format!("Failed to delete cookies: {e}"),
+          code: None,
+        })
+      })
+  }
+
+  find(tab_id)
+    .and_then(|chaser| read_cookies(chaser.clone()).map_ok(move |cookies| (chaser, cookies)))
+    .map_ok(move |found| (found, dto))
+    .and_then(remove_cookies)
+    .await
+}
+
+/// Exports the cookies and localStorage of the tab in the Playwright `storageState` shape.
+///
+/// # Behavior
+///
+/// - Resolves the tab by ID.
+/// - Collects the HTTP(S) frames of the tab's current page.
+/// - Reads every cookie of the tab's browser context when the tab is `isolated` or in a
+///   session (`Storage.getCookies`), so cookies of other domains such as an SSO domain
+///   are kept. In the shared profile, which holds the cookies of every shared tab, only
+///   the cookies applicable to the frames' URLs are read.
+/// - Reads localStorage for each frame origin; origins whose storage cannot be read
+///   (e.g. out-of-process iframes) are skipped.
+///
+/// # Arguments
+///
+/// - `tab_id`: The ID of the tab to export.
+///
+/// # Errors
+///
+/// Returns an `Error` if:
+/// - The tab with the given ID does not exist.
+/// - Reading the frame tree or the cookies fails.
+///
+/// # Examples
+///
+/// ```ignore
+/// let state = api::storage_state(tab_id).await?;
+/// ```
+pub async fn storage_state(tab_id: &str) -> Result<StorageStateDto, Error> {
+  fn collect_frame_urls(tree: &FrameTree, urls: &mut Vec<String>) {
+    if tree.frame.url.starts_with("http") {
+      urls.push(tree.frame.url.clone());
+    }
+    tree
+      .child_frames
+      .iter()
+      .flatten()
+      .for_each(|child| collect_frame_urls(child, urls));
+  }
+  async fn read_frame_urls(
+    chaser: Arc<ChaserPage>,
+  ) -> Result<(Arc<ChaserPage>, Vec<String>), Error> {
+    chaser
+      .raw_page()
+      .execute(GetFrameTreeParams::default())
+      .await
+      .map(|tree| {
+        let mut urls = Vec::new();
+        collect_frame_urls(&tree.result.frame_tree, &mut urls);
+        (chaser, urls)
+      })
+      .map_err(|e| {
+        Error::Operation(ErrorInfo {
+          message: format!("Failed to read frame tree: {e}"),
+          code: None,
+        })
+      })
+  }
+  async fn read_cookies_for(
+    (chaser, context_id, urls): (Arc<ChaserPage>, Option<BrowserContextId>, Vec<String>),
+  ) -> Result<(Arc<ChaserPage>, Vec<String>, Vec<Cookie>), Error> {
+    let page = chaser.raw_page();
+    let cookies = match context_id {
+      Some(id) => {
+        let params = storage::GetCookiesParams {
+          browser_context_id: Some(id),
+        };
+        page
+          .execute(params)
+          .await
+          .map(|cookies| cookies.result.cookies)
+      }
+      None if urls.is_empty() => Ok(Vec::new()),
+      None => {
+        let params = GetCookiesParams {
+          urls: Some(urls.clone()),
+        };
+        page
+          .execute(params)
+          .await
+          .map(|cookies| cookies.result.cookies)
+      }
+    };
+
+    cookies.map(|cookies| (chaser, urls, cookies)).map_err(|e| {
+      Error::Operation(ErrorInfo {
+        message: format!("Failed to get cookies: {e}"),
+        code: None,
+      })
+    })
+  }
+  async fn read_local_storage(chaser: &Arc<ChaserPage>, origin: String) -> Option<OriginStateDto> {
+    let storage_id = StorageId {
+      security_origin: Some(origin.clone()),
+      ..StorageId::new(true)
+    };
+
+    match chaser
+      .raw_page()
+      .execute(GetDomStorageItemsParams::new(storage_id))
+      .await
+    {
+      Ok(items) => Some(OriginStateDto {
+        origin,
+        local_storage: items
+          .result
+          .entries
+          .into_iter()
+          .filter_map(|item| match item.inner().as_slice() {
+            [name, value] => Some(StorageItemDto {
+              name: name.clone(),
+              value: value.clone(),
+            }),
+            _ => None,
+          })
+          .collect(),
+      }),
+      Err(e) => {
+        tracing::debug!("Skipping localStorage of {origin}: {e}");
+        None
+      }
+    }
+  }
+  async fn build_state(
+    (chaser, urls, cookies): (Arc<ChaserPage>, Vec<String>, Vec<Cookie>),
+  ) -> Result<StorageStateDto, Error> {
+    let mut origins = urls
+      .iter()
+      .filter_map(|url| Url::parse(url).ok())
+      .map(|url| url.origin().ascii_serialization())
+      .collect::<Vec<_>>();
+    origins.sort();
+    origins.dedup();
+
+    let origins = future::join_all(
+      origins
+        .into_iter()
+        .map(|origin| read_local_storage(&chaser, origin)),
+    )
+    .await
+    .into_iter()
+    .flatten()
+    .filter(|origin| !origin.local_storage.is_empty())
+    .collect();
+
+    let cookies = cookies
+      .into_iter()
+      .map(|cookie| StorageCookieDto {
+        name: cookie.name,
+        value: cookie.value,
+        domain: cookie.domain,
+        path: cookie.path,
+        expires: cookie.expires,
+        http_only: cookie.http_only,
+        secure: cookie.secure,
+        // Chrome treats cookies without SameSite as Lax.
+        same_site: cookie.same_site.unwrap_or(CookieSameSite::Lax),
+      })
+      .collect();
+
+    Ok(StorageStateDto { cookies, origins })
+  }
+
+  let (chaser, context_id) =
+    live_tab(&mut *TABS.lock().await, tab_id).map(|tab| (tab.page.clone(), tab.context.id()))?;
+
+  read_frame_urls(chaser)
+    .map_ok(move |(chaser, urls)| (chaser, context_id, urls))
+    .and_then(read_cookies_for)
+    .and_then(build_state)
+    .await
+}
+
+/// Returns the request blocking rules of the tab and how many requests they blocked.
+///
+/// # Behavior
+///
+/// - Resolves the tab by ID and counts as activity for it.
+/// - Returns empty rules and a zero count when the tab never intercepted requests.
+///
+/// # Arguments
+///
+/// - `tab_id`: The ID of the tab.
+///
+/// # Errors
+///
+/// Returns `Error::NotFound` if the tab with the given ID does not exist.
+///
+/// # Examples
+///
+/// ```ignore
+/// let rules = api::network_rules(tab_id).await?;
+/// ```
+pub async fn network_rules(tab_id: &str) -> Result<NetworkRulesDto, Error> {
+  find_interception(tab_id).await.map(|(_, interception)| {
+    interception.map_or_else(
+      || NetworkRulesDto {
+        block: BlockDto::default(),
+        blocked: 0,
+      },
+      |interception| interception.describe(),
+    )
+  })
+}
+
+/// Replaces the request blocking rules of the tab.
+///
+/// # Behavior
+///
+/// - Validates and compiles the rules before touching the tab.
+/// - Updates the running interception of the tab, or starts `Fetch` request interception
+///   when the tab has none yet.
+/// - Keeps the count of requests blocked so far.
+///
+/// # Arguments
+///
+/// - `tab_id`: The ID of the tab.
+/// - `dto`: The new blocking rules.
+///
+/// # Errors
+///
+/// Returns an `Error` if:
+/// - The rules are invalid (see `BlockRules::compile`).
+/// - The tab with the given ID does not exist.
+/// - Starting the request interception fails.
+///
+/// # Examples
+///
+/// ```ignore
+/// let rules = api::set_network_rules(tab_id, BlockDto::default()).await?;
+/// ```
+pub async fn set_network_rules(tab_id: &str, dto: BlockDto) -> Result<NetworkRulesDto, Error> {
+  future::ready(BlockRules::compile(dto))
+    .and_then(|rules| async move {
+      let interception = ensure_interception(tab_id).await?;
+      interception.set_block(rules);
+      Ok(interception.describe())
+    })
+    .await
+}
+
+/// Registers a route rule on the tab.
+///
+/// # Behavior
+///
+/// - Validates and compiles the rule before touching the tab.
+/// - Starts `Fetch` request interception when the tab has none yet.
+/// - Requests matching the rule are fulfilled, aborted or continued as it says; the most
+///   recently added matching rule wins and routes take precedence over blocking rules.
+///
+/// # Arguments
+///
+/// - `tab_id`: The ID of the tab.
+/// - `dto`: The route rule.
+///
+/// # Errors
+///
+/// Returns an `Error` if:
+/// - The rule is invalid (see `Route::compile`).
+/// - The tab with the given ID does not exist.
+/// - Starting the request interception fails.
+///
+/// # Examples
+///
+/// ```ignore
+/// let route = api::add_route(tab_id, dto).await?;
+/// ```
+pub async fn add_route(tab_id: &str, dto: RouteDto) -> Result<RouteAddedDto, Error> {
+  future::ready(Route::compile(dto))
+    .and_then(|route| async move {
+      let interception = ensure_interception(tab_id).await?;
+      Ok(RouteAddedDto {
+        route_id: interception.add_route(route),
+      })
+    })
+    .await
+}
+
+/// Removes one or every route rule of the tab.
+///
+/// # Arguments
+///
+/// - `tab_id`: The ID of the tab.
+/// - `dto`: The route to remove; every route when `route_id` is omitted.
+///
+/// # Errors
+///
+/// Returns `Error::NotFound` if the tab or the given route does not exist.
+///
+/// # Examples
+///
+/// ```ignore
+/// api::delete_routes(tab_id, DeleteRoutesDto { route_id: None }).await?;
+/// ```
+pub async fn delete_routes(tab_id: &str, dto: DeleteRoutesDto) -> Result<(), Error> {
+  let (_, interception) = find_interception(tab_id).await?;
+  let removed =
+    interception.is_some_and(|interception| interception.remove_routes(dto.route_id.as_deref()));
+
+  match dto.route_id {
+    Some(route_id) if !removed => Err(Error::NotFound(format!("route_id {route_id}"))),
+    _ => Ok(()),
+  }
+}
+
+/// Returns the console messages captured for the tab.
+///
+/// # Behavior
+///
+/// - Resolves the tab by ID and counts as activity for it.
+/// - Returns the buffered `console.*` calls and browser log entries (the latest 1 000),
+///   oldest first, filtered by the minimum `level` and logged after `since`.
+///
+/// # Arguments
+///
+/// - `tab_id`: The ID of the tab.
+/// - `query`: Optional level and time filters.
+///
+/// # Errors
+///
+/// Returns an `Error` if:
+/// - The tab with the given ID does not exist.
+/// - The tab was opened without `console`.
+///
+/// # Examples
+///
+/// ```ignore
+/// let messages = api::console(tab_id, ConsoleQueryDto { level: None, since: None }).await?;
+/// ```
+pub async fn console(
+  tab_id: &str,
+  query: ConsoleQueryDto,
+) -> Result<Vec<ConsoleMessageDto>, Error> {
+  live_tab(&mut *TABS.lock().await, tab_id).and_then(|tab| {
+    tab
+      .console
+      .as_ref()
+      .map(|recorder| recorder.messages(&query))
+      .ok_or_else(|| {
+        Error::NotSupported(format!(
+          "console capture is not enabled for tab_id {tab_id}"
+        ))
+      })
+  })
+}
+
+/// Returns the uncaught exceptions and the renderer crash recorded for the tab.
+///
+/// # Behavior
+///
+/// - Works on crashed tabs too, unlike the other tab calls; counts as activity for the tab.
+/// - Returns the latest 100 errors, oldest first. Uncaught exceptions are only recorded
+///   for tabs opened with `errors` or `console`, which enable the `Runtime` domain they
+///   come from; `exceptions` tells whether they are.
+///
+/// # Arguments
+///
+/// - `tab_id`: The ID of the tab.
+///
+/// # Errors
+///
+/// Returns `Error::NotFound` if the tab with the given ID does not exist.
+///
+/// # Examples
+///
+/// ```ignore
+/// let errors = api::errors(tab_id).await?;
+/// ```
+pub async fn errors(tab_id: &str) -> Result<PageErrorsDto, Error> {
+  TABS
+    .lock()
+    .await
+    .get_mut(tab_id)
+    .map(|tab| {
+      tab.last_activity = SystemTime::now();
+      tab.errors.as_ref().map_or_else(
+        || PageErrorsDto {
+          crashed: false,
+          exceptions: false,
+          errors: Vec::new(),
+        },
+        ErrorRecorder::export,
+      )
+    })
+    .ok_or_else(|| Error::NotFound(format!("tab_id {tab_id}")))
+}
+
+/// Returns the pending JavaScript dialogs of the tab and the history of closed ones.
+///
+/// # Behavior
+///
+/// - Resolves the tab by ID and counts as activity for it.
+/// - Lists the dialogs waiting for an answer and the latest 100 closed dialogs, oldest
+///   first, with their message and how they were answered.
+///
+/// # Arguments
+///
+/// - `tab_id`: The ID of the tab.
+///
+/// # Errors
+///
+/// Returns an `Error` if:
+/// - The tab with the given ID does not exist.
+/// - The renderer of the tab crashed (`Error::Crashed`).
+///
+/// # Examples
+///
+/// ```ignore
+/// let dialogs = api::dialogs(tab_id).await?;
+/// ```
+pub async fn dialogs(tab_id: &str) -> Result<DialogsDto, Error> {
+  live_tab(&mut *TABS.lock().await, tab_id).map(|tab| {
+    tab.dialogs.as_ref().map_or_else(
+      || DialogsDto {
+        pending: Vec::new(),
+        history: Vec::new(),
+      },
+      DialogHandler::describe,
+    )
+  })
+}
+
+/// Accepts or dismisses a pending JavaScript dialog of the tab.
+///
+/// # Behavior
+///
+/// - Resolves the tab by ID and counts as activity for it.
+/// - Answers the dialog with `Page.handleJavaScriptDialog`, entering `dto.prompt_text` into
+///   a `prompt` dialog when accepting it; the page continues once the dialog is closed.
+///
+/// # Arguments
+///
+/// - `tab_id`: The ID of the tab.
+/// - `dialog_id`: The ID of the pending dialog.
+/// - `dto`: Whether to accept the dialog and the optional prompt text.
+///
+/// # Errors
+///
+/// Returns an `Error` if:
+/// - The tab with the given ID does not exist, or no dialog with the given ID is pending.
+/// - The renderer of the tab crashed (`Error::Crashed`).
+/// - Answering the dialog fails.
+///
+/// # Examples
+///
+/// ```ignore
+/// api::answer_dialog(tab_id, dialog_id, AnswerDialogDto { accept: true, prompt_text: None })
+///   .await?;
+/// ```
+pub async fn answer_dialog(
+  tab_id: &str,
+  dialog_id: &str,
+  dto: AnswerDialogDto,
+) -> Result<(), Error> {
+  async fn handle_dialog((chaser, dto): (Arc<ChaserPage>, AnswerDialogDto)) -> Result<(), Error> {
+    let params = HandleJavaScriptDialogParams {
+      prompt_text: dto.prompt_text,
+      ..HandleJavaScriptDialogParams::new(dto.accept)
+    };
+
+    chaser
+      .raw_page()
+      .execute(params)
+      .await
+      .map(|_| ())
+      .map_err(|e| {
+        Error::Operation(ErrorInfo {
+          message: format!("Failed to answer dialog: {e}"),
+          code: None,
+        })
+      })
+  }
+
+  let pending = live_tab(&mut *TABS.lock().await, tab_id).and_then(|tab| {
+    tab
+      .dialogs
+      .as_ref()
+      .is_some_and(|handler| handler.is_pending(dialog_id))
+      .then(|| tab.page.clone())
+      .ok_or_else(|| Error::NotFound(format!("pending dialog_id {dialog_id}")))
+  });
+
+  future::ready(pending.map(|chaser| (chaser, dto)))
+    .and_then(handle_dialog)
+    .await
+}
+
+/// Replaces the extra HTTP headers sent with every request of the tab.
+///
+/// # Behavior
+///
+/// - Resolves the tab by ID and counts as activity for it.
+/// - Replaces the headers set when opening the tab or by a previous call
+///   (`Network.setExtraHTTPHeaders`); an empty map removes them.
+/// - Applies to requests started after the call.
+///
+/// # Arguments
+///
+/// - `tab_id`: The ID of the tab.
+/// - `dto`: The new headers.
+///
+/// # Errors
+///
+/// Returns an `Error` if:
+/// - The tab with the given ID does not exist.
+/// - Setting the headers fails.
+///
+/// # Examples
+///
+/// ```ignore
+/// api::set_headers(tab_id, HeadersDto { headers }).await?;
+/// ```
+pub async fn set_headers(tab_id: &str, dto: HeadersDto) -> Result<(), Error> {
+  find(tab_id)
+    .and_then(|chaser| async move { set_extra_headers(&chaser, dto.headers).await })
+    .await
+}
+
+/// Replaces the credentials answered to the auth challenges of websites on the tab.
+///
+/// # Behavior
+///
+/// - Starts `Fetch` request interception when the tab has none yet.
+/// - Answers later HTTP Basic/Digest challenges with the given credentials once per
+///   request; a repeated challenge (wrong credentials) is cancelled.
+/// - `None` stops answering the challenges, leaving them to the browser's default behavior.
+///
+/// # Arguments
+///
+/// - `tab_id`: The ID of the tab.
+/// - `dto`: The new credentials, or `None` to remove them.
+///
+/// # Errors
+///
+/// Returns an `Error` if:
+/// - The tab with the given ID does not exist.
+/// - Starting the request interception fails.
+///
+/// # Examples
+///
+/// ```ignore
+/// api::set_http_credentials(tab_id, Some(dto)).await?;
+/// ```
+pub async fn set_http_credentials(
+  tab_id: &str,
+  dto: Option<HttpCredentialsDto>,
+) -> Result<(), Error> {
+  let credentials = dto.map(Credentials::from);
+
+  match credentials {
+    Some(credentials) => ensure_interception(tab_id)
+      .await
+      .map(|interception| interception.set_http_credentials(Some(credentials))),
+    None => find_interception(tab_id).await.map(|(_, interception)| {
+      if let Some(interception) = interception {
+        interception.set_http_credentials(None);
+      }
+    }),
+  }
+}
+
+/// Returns the network traffic recorded for the tab as a HAR 1.2 archive.
+///
+/// # Behavior
+///
+/// - Resolves the tab by ID and counts as activity for it.
+/// - Includes every request since the tab was opened, with response bodies when the
+///   recording was started with `content`.
+///
+/// # Arguments
+///
+/// - `tab_id`: The ID of the tab.
+///
+/// # Errors
+///
+/// Returns an `Error` if:
+/// - The tab with the given ID does not exist.
+/// - The tab was opened without `har`.
+///
+/// # Examples
+///
+/// ```ignore
+/// let har = api::har(tab_id).await?;
+/// ```
+pub async fn har(tab_id: &str) -> Result<HarDto, Error> {
+  live_tab(&mut *TABS.lock().await, tab_id).and_then(|tab| {
+    tab.har.as_ref().map(HarRecorder::export).ok_or_else(|| {
+      Error::NotSupported(format!("HAR recording is not enabled for tab_id {tab_id}"))
+    })
+  })
+}
+
+/// Waits for a network response of the tab and returns it with its body.
+///
+/// # Behavior
+///
+/// - Resolves the tab by ID.
+/// - Arms a listener for the first response whose URL matches the `dto.url` glob and whose
+///   request used `dto.method`, before anything else happens.
+/// - Clicks the `dto.click` element once armed, so the response it triggers is not missed.
+/// - Waits for the response to finish loading and reads its body; text arriving
+///   base64-encoded is decoded.
+///
+/// # Arguments
+///
+/// - `tab_id`: The ID of the tab.
+/// - `dto`: URL glob, optional method, timeout and optional element to click.
+///
+/// # Errors
+///
+/// Returns an `Error` if:
+/// - The tab or the element to click does not exist, or clicking it fails.
+/// - The URL glob is invalid.
+/// - The matching request fails or its body cannot be read.
+/// - No matching response finishes within `dto.timeout_ms` (`Error::Timeout`).
+/// - A JavaScript dialog is pending or opens meanwhile (`Error::Blocked`).
+///
+/// # Examples
+///
+/// ```ignore
+/// let response = api::wait_for_response(tab_id, dto).await?;
+/// ```
+pub async fn wait_for_response(
+  tab_id: &str,
+  dto: WaitForResponseDto,
+) -> Result<NetworkResponseDto, Error> {
+  async fn arm(
+    (chaser, dto): (Arc<ChaserPage>, WaitForResponseDto),
+  ) -> Result<(Arc<ChaserPage>, ResponseWaiter, WaitForResponseDto), Error> {
+    ResponseWaiter::arm(chaser.raw_page(), dto.url.as_str(), dto.method.clone())
+      .await
+      .map(|waiter| (chaser, waiter, dto))
+  }
+  async fn trigger(
+    (chaser, waiter, dto): (Arc<ChaserPage>, ResponseWaiter, WaitForResponseDto),
+  ) -> Result<(ResponseWaiter, WaitForResponseDto), Error> {
+    let Some(selector) = &dto.click else {
+      return Ok((waiter, dto));
+    };
+
+    let element = find_element(&chaser, selector.as_str()).await?;
+    element.click().await.map_err(|e| {
+      Error::Operation(ErrorInfo {
+        message: format!("Failed to click element '{selector}': {e}"),
+        code: None,
+      })
+    })?;
+
+    Ok((waiter, dto))
   }
-  async fn close_tab(
-    (chaser, cookie_error): (Arc<ChaserPage>, Option<Error>),
-  ) -> Result<(), Error> {
-    match close_page(chaser).await {
-      Ok(()) => match cookie_error {
-        Some(cookie_error) => Err(cookie_error),
-        None => Ok(()),
-      },
-      Err(close_error) => match cookie_error {
-        Some(cookie_error) => Err(Error::Operation(ErrorInfo {
-          message: format!(
-            "Failed to close tab after cookie cleanup error. cookie_error: {cookie_error}; close_error: {close_error}"
-          ),
-          code: None,
-        })),
-        None => Err(close_error),
-      },
-    }
+  async fn await_response(
+    (waiter, dto): (ResponseWaiter, WaitForResponseDto),
+  ) -> Result<NetworkResponseDto, Error> {
+    let limit = bounded_timeout(dto.timeout_ms);
+    timeout(limit, waiter.wait()).await.unwrap_or_else(|_| {
+      Err(Error::Timeout(format!(
+        "No response matching {} within {} ms",
+        dto.url,
+        limit.as_millis()
+      )))
+    })
   }
 
-  remove_tab(tab_id)
-    .and_then(get_cookies)
-    .and_then(clear_cookies)
-    .and_then(close_tab)
-    .await
+  let (chaser, guard) = find_guarded(tab_id).await?;
+  let response = arm((chaser, dto))
+    .and_then(trigger)
+    .and_then(await_response);
+
+  unblocked(guard, response).await
 }
 
 /// Clicks the element with the given selector in the tab.
@@ -284,6 +1825,7 @@ pub async fn close(tab_id: &str) -> Result<(), Error> {
 /// - The element is not found.
 /// - Clicking the element fails.
 /// - Waiting for navigation fails.
+/// - A JavaScript dialog is pending or opens meanwhile (`Error::Blocked`).
 ///
 /// # Examples
 ///
@@ -403,16 +1945,14 @@ pub async fn click(tab_id: &str, dto: ClickDto) -> Result<String, Error> {
     )
   }
 
-  let selector = dto.selector;
-
-  find(tab_id)
-    .map_ok(move |chaser| (chaser, selector))
-    .and_then(resolve_click_target)
+  let (chaser, guard) = find_guarded(tab_id).await?;
+  let clicked = resolve_click_target((chaser, dto.selector))
     .and_then(click_element)
     .and_then(wait_for_possible_navigation)
     .and_then(wait_for_stable_location)
-    .and_then(get_title)
-    .await
+    .and_then(get_title);
+
+  unblocked(guard, clicked).await
 }
 
 /// Checks whether an element with the selector exists in the tab.
@@ -420,30 +1960,38 @@ pub async fn click(tab_id: &str, dto: ClickDto) -> Result<String, Error> {
 /// # Behavior
 ///
 /// - Resolves the tab by ID.
-/// - Returns `true` if the element is found, otherwise `false`.
+/// - Returns `true` if the element is found, otherwise `false` (also for a tab that does
+///   not exist).
 ///
 /// # Arguments
 ///
 /// - `tab_id`: The ID of the tab to operate on.
 /// - `dto`: Exists payload including the selector.
 ///
+/// # Errors
+///
+/// Returns `Error::Blocked` if a JavaScript dialog is pending or opens meanwhile.
+///
 /// # Examples
 ///
 /// ```ignore
-/// let has_modal = api::exists(tab_id, ExistsDto { selector: "#modal".into() }).await;
+/// let has_modal = api::exists(tab_id, ExistsDto { selector: "#modal".into() }).await?;
 /// ```
-pub async fn exists(tab_id: &str, dto: ExistsDto) -> bool {
+pub async fn exists(tab_id: &str, dto: ExistsDto) -> Result<bool, Error> {
   async fn element_exists((page, selector): (Arc<ChaserPage>, String)) -> Result<bool, Error> {
     find_element(&page, selector.as_str()).await.map(|_| true)
   }
 
   let selector = dto.selector;
+  let found = match find_guarded(tab_id).await {
+    Ok((page, guard)) => unblocked(guard, element_exists((page, selector))).await,
+    Err(e) => Err(e),
+  };
 
-  find(tab_id)
-    .map_ok(move |page| (page, selector))
-    .and_then(element_exists)
-    .await
-    .unwrap_or(false)
+  match found {
+    Err(e @ Error::Blocked(_)) => Err(e),
+    found => Ok(found.unwrap_or(false)),
+  }
 }
 
 /// Extracts content from the element with the given selector in the tab.
@@ -466,6 +2014,7 @@ pub async fn exists(tab_id: &str, dto: ExistsDto) -> bool {
 /// - The tab is not found.
 /// - The element is not found.
 /// - Getting the content fails.
+/// - A JavaScript dialog is pending or opens meanwhile (`Error::Blocked`).
 ///
 /// # Examples
 ///
@@ -493,13 +2042,10 @@ pub async fn extract(tab_id: &str, dto: ExtractDto) -> Result<String, Error> {
       .map(|opt| opt.unwrap_or_default())
   }
 
-  let selector = dto.selector;
+  let (page, guard) = find_guarded(tab_id).await?;
+  let extracted = resolve_extract_target((page, dto.selector)).and_then(extract_inner_text);
 
-  find(tab_id)
-    .map_ok(move |page| (page, selector))
-    .and_then(resolve_extract_target)
-    .and_then(extract_inner_text)
-    .await
+  unblocked(guard, extracted).await
 }
 
 /// Executes JavaScript code on the element with the given selector in the tab,
@@ -522,6 +2068,7 @@ pub async fn extract(tab_id: &str, dto: ExtractDto) -> Result<String, Error> {
 /// - The tab is not found.
 /// - The element is not found (if selector is provided).
 /// - Evaluating the JavaScript fails.
+/// - A JavaScript dialog is pending or opens meanwhile (`Error::Blocked`).
 ///
 /// # Examples
 ///
@@ -570,14 +2117,11 @@ pub async fn execute(tab_id: &str, dto: ExecuteDto) -> Result<String, Error> {
       })
   }
 
-  let selector = dto.selector;
-  let function = dto.function;
+  let (page, guard) = find_guarded(tab_id).await?;
+  let executed =
+    resolve_execution_target((page, dto.selector, dto.function)).and_then(evaluate_function);
 
-  find(tab_id)
-    .map_ok(move |page| (page, selector, function))
-    .and_then(resolve_execution_target)
-    .and_then(evaluate_function)
-    .await
+  unblocked(guard, executed).await
 }
 
 /// Fills form inputs in the tab with the specified values.
@@ -598,6 +2142,7 @@ pub async fn execute(tab_id: &str, dto: ExecuteDto) -> Result<String, Error> {
 /// - The tab with the given ID does not exist.
 /// - Finding an element fails.
 /// - Filling an element fails.
+/// - A JavaScript dialog is pending or opens meanwhile (`Error::Blocked`).
 ///
 /// # Examples
 ///
@@ -692,10 +2237,9 @@ pub async fn fill(tab_id: &str, dto: FillDto) -> Result<(), Error> {
       .await
   }
 
-  find(tab_id)
-    .map_ok(move |chaser| (chaser, dto))
-    .and_then(fill_inputs)
-    .await
+  let (chaser, guard) = find_guarded(tab_id).await?;
+
+  unblocked(guard, fill_inputs((chaser, dto))).await
 }
 
 /// Applies human-like behaviors to the tab to avoid detection.
@@ -714,6 +2258,7 @@ pub async fn fill(tab_id: &str, dto: FillDto) -> Result<(), Error> {
 /// Returns an `Error` if:
 /// - The tab with the given ID does not exist.
 /// - JavaScript evaluation fails.
+/// - A JavaScript dialog is pending or opens meanwhile (`Error::Blocked`).
 ///
 /// # Examples
 ///
@@ -744,7 +2289,9 @@ pub async fn humanize(tab_id: &str) -> Result<(), Error> {
       })
   }
 
-  find(tab_id).and_then(apply_humanize).await
+  let (page, guard) = find_guarded(tab_id).await?;
+
+  unblocked(guard, apply_humanize(page)).await
 }
 
 /// Returns a PNG screenshot of the tab.
@@ -764,6 +2311,7 @@ pub async fn humanize(tab_id: &str) -> Result<(), Error> {
 /// Returns an `Error` if:
 /// - The tab with the given ID does not exist.
 /// - Capturing the screenshot fails.
+/// - A JavaScript dialog is pending or opens meanwhile (`Error::Blocked`).
 ///
 /// # Examples
 ///
@@ -784,10 +2332,771 @@ pub async fn screenshot(tab_id: &str) -> Result<Vec<u8>, Error> {
       })
   }
 
-  find(tab_id).and_then(capture_screenshot).await
+  let (page, guard) = find_guarded(tab_id).await?;
+
+  unblocked(guard, capture_screenshot(page)).await
+}
+#[inline]
+fn parse_url(url: &str) -> Result<Url, Error> {
+  Url::parse(url).map_err(|e| {
+    Error::Operation(ErrorInfo {
+      message: format!("Invalid URL: {e}"),
+      code: None,
+    })
+  })
+}
+async fn run_navigation(
+  tab_id: &str,
+  navigation: Navigation,
+  wait_until: WaitUntilDto,
+  limit: Duration,
+) -> Result<NavigationDto, Error> {
+  let (chaser, guard) = find_guarded(tab_id).await?;
+  let navigated = async {
+    let request = load_page(&chaser, navigation, &wait_until, limit).await?;
+    Ok(describe_navigation(&chaser, request).await)
+  };
+
+  unblocked(guard, navigated).await
+}
+/// Describes the page after a navigation from its main document request.
+async fn describe_navigation(chaser: &ChaserPage, request: ArcHttpRequest) -> NavigationDto {
+  let page = chaser.raw_page();
+  let (url, title) = future::join(page.url(), page.get_title()).await;
+  let response = request
+    .as_ref()
+    .and_then(|request| request.response.as_ref());
+
+  NavigationDto {
+    url: url.ok().flatten(),
+    title: title.ok().flatten(),
+    status: response.map(|response| response.status),
+    headers: response
+      .and_then(|response| response.headers.inner().as_object())
+      .map(|headers| {
+        headers
+          .iter()
+          .filter_map(|(name, value)| {
+            value
+              .as_str()
+              .map(|value| (name.clone(), value.to_string()))
+          })
+          .collect()
+      })
+      .unwrap_or_default(),
+    redirect_chain: request
+      .iter()
+      .flat_map(|request| request.redirect_chain.iter())
+      .filter_map(|redirect| redirect.response.as_ref())
+      .map(|response| RedirectDto {
+        url: response.url.clone(),
+        status: response.status,
+      })
+      .collect(),
+  }
+}
+/// Performs a navigation of the main frame and waits until `wait_until` is reached.
+///
+/// Returns the request of the main document, if it was loaded over the network. With
+/// `DOMContentLoaded`, the response is taken from the `Network` events of the navigation,
+/// as the wait usually ends before the `load` event.
+async fn load_page(
+  chaser: &Arc<ChaserPage>,
+  navigation: Navigation,
+  wait_until: &WaitUntilDto,
+  limit: Duration,
+) -> Result<ArcHttpRequest, Error> {
+  fn navigation_error(e: impl std::fmt::Display) -> Error {
+    Error::Operation(ErrorInfo {
+      message: format!("Failed to navigate: {e}"),
+      code: None,
+    })
+  }
+  fn cdp_error(e: CdpError) -> Error {
+    match e {
+      CdpError::Timeout => Error::Timeout("navigation timed out in the browser".to_string()),
+      e => navigation_error(e),
+    }
+  }
+  async fn history_entry(page: &Page, offset: i64) -> Result<i64, Error> {
+    let history = page
+      .execute(GetNavigationHistoryParams::default())
+      .await
+      .map_err(navigation_error)?
+      .result;
+
+    usize::try_from(history.current_index + offset)
+      .ok()
+      .and_then(|index| history.entries.get(index))
+      .map(|entry| entry.id)
+      .ok_or_else(|| {
+        Error::NotSupported(format!(
+          "no {} page in the tab history",
+          if offset < 0 { "previous" } else { "next" }
+        ))
+      })
+  }
+  async fn navigated(page: &Page, navigation: Navigation) -> Result<ArcHttpRequest, Error> {
+    match navigation {
+      Navigation::Goto { url, referrer } => {
+        let params = NavigateParams {
+          referrer,
+          ..NavigateParams::new(url.as_str())
+        };
+        page
+          .execute(params)
+          .await
+          .map_err(cdp_error)
+          .and_then(|response| {
+            response
+              .result
+              .error_text
+              .map_or(Ok(()), |e| Err(navigation_error(e)))
+          })?;
+      }
+      Navigation::Back | Navigation::Forward => {
+        let offset = if matches!(navigation, Navigation::Back) {
+          -1
+        } else {
+          1
+        };
+        let entry_id = history_entry(page, offset).await?;
+        page
+          .execute(NavigateToHistoryEntryParams::new(entry_id))
+          .await
+          .map(|_| ())
+          .map_err(cdp_error)?;
+      }
+      Navigation::Reload => page
+        .execute(ReloadParams::default())
+        .await
+        .map(|_| ())
+        .map_err(cdp_error)?,
+    }
+
+    page.wait_for_navigation_response().await.map_err(cdp_error)
+  }
+  async fn lifecycle_reached(
+    mut events: EventStream<EventLifecycleEvent>,
+    frame_id: Option<FrameId>,
+    name: &str,
+  ) {
+    // Only events of the document created by this navigation count, so the
+    // loader is taken from its `init` event.
+    let mut loader_id = None;
+    while let Some(event) = events.next().await {
+      if frame_id.as_ref().is_some_and(|id| *id != event.frame_id) {
+        continue;
+      }
+      if event.name == "init" {
+        loader_id = Some(event.loader_id.clone());
+      } else if event.name == name && loader_id.as_ref() == Some(&event.loader_id) {
+        return;
+      }
+    }
+    // The page is gone; leave it to the time limit.
+    future::pending::<()>().await;
+  }
+  async fn document_response(
+    requests: EventStream<EventRequestWillBeSent>,
+    responses: EventStream<EventResponseReceived>,
+    frame_id: Option<FrameId>,
+  ) -> ArcHttpRequest {
+    enum Event {
+      Request(Arc<EventRequestWillBeSent>),
+      Response(Arc<EventResponseReceived>),
+    }
+
+    // A navigation request carries the ID of the loader of the document it creates.
+    let is_document = |request_id: &RequestId, loader_id: &LoaderId, frame: Option<&FrameId>| {
+      request_id.inner() == loader_id.inner()
+        && frame_id.as_ref().is_none_or(|id| Some(id) == frame)
+    };
+    let to_request = |request_id: &RequestId,
+                      frame: Option<&FrameId>,
+                      response: &Response,
+                      redirect_chain: Vec<HttpRequest>| {
+      let mut request = HttpRequest::new(
+        request_id.clone(),
+        frame.cloned(),
+        None,
+        false,
+        redirect_chain,
+      );
+      request.response = Some(response.clone());
+      request
+    };
+    let mut events = stream::select(requests.map(Event::Request), responses.map(Event::Response));
+    let mut redirect_chain = Vec::new();
+    while let Some(event) = events.next().await {
+      match event {
+        Event::Request(event)
+          if is_document(&event.request_id, &event.loader_id, event.frame_id.as_ref()) =>
+        {
+          match &event.redirect_response {
+            Some(response) => redirect_chain.push(to_request(
+              &event.request_id,
+              event.frame_id.as_ref(),
+              response,
+              Vec::new(),
+            )),
+            None => redirect_chain.clear(),
+          }
+        }
+        Event::Response(event)
+          if is_document(&event.request_id, &event.loader_id, event.frame_id.as_ref()) =>
+        {
+          return Some(Arc::new(to_request(
+            &event.request_id,
+            event.frame_id.as_ref(),
+            &event.response,
+            redirect_chain,
+          )));
+        }
+        _ => {}
+      }
+    }
+    // The page is gone; leave it to the navigation.
+    future::pending().await
+  }
+  async fn selector_found(chaser: &Arc<ChaserPage>, selector: &str) {
+    while find_element(chaser, selector).await.is_err() {
+      sleep(Duration::from_millis(100)).await;
+    }
+  }
+
+  let page = chaser.raw_page();
+  let frame_id = page.mainframe().await.ok().flatten();
+  let events = page
+    .event_listener::<EventLifecycleEvent>()
+    .await
+    .map_err(navigation_error)?;
+
+  let loaded = async {
+    match wait_until {
+      WaitUntilDto::Load => navigated(page, navigation).await,
+      WaitUntilDto::DomContentLoaded => {
+        let requests = page
+          .event_listener::<EventRequestWillBeSent>()
+          .await
+          .map_err(navigation_error)?;
+        let responses = page
+          .event_listener::<EventResponseReceived>()
+          .await
+          .map_err(navigation_error)?;
+        // Only the wait is cut short: without a network response of the document
+        // (e.g. a page from the back/forward cache), it lasts until `load`.
+        let reached = future::join(
+          document_response(requests, responses, frame_id.clone()),
+          lifecycle_reached(events, frame_id, "DOMContentLoaded"),
+        );
+        tokio::select! {
+          request = navigated(page, navigation) => request,
+          (request, ()) = reached => Ok(request),
+        }
+      }
+      WaitUntilDto::NetworkIdle => {
+        let request = navigated(page, navigation).await?;
+        lifecycle_reached(events, frame_id, "networkIdle").await;
+        Ok(request)
+      }
+      WaitUntilDto::Selector(selector) => {
+        let request = navigated(page, navigation).await?;
+        selector_found(chaser, selector).await;
+        Ok(request)
+      }
+    }
+  };
+
+  timeout(limit, loaded).await.unwrap_or_else(|_| {
+    Err(Error::Timeout(format!(
+      "navigation did not finish within {} ms",
+      limit.as_millis()
+    )))
+  })
+}
+async fn dispose_context(context: TabContext) -> Result<(), Error> {
+  match context {
+    TabContext::Shared | TabContext::Session { .. } => Ok(()),
+    TabContext::Isolated { id, browser } => {
+      browser.dispose_browser_context(id).await.map_err(|e| {
+        Error::Operation(ErrorInfo {
+          message: format!("Failed to dispose browser context: {e}"),
+          code: None,
+        })
+      })
+    }
+  }
+}
+/// Closes a tab that never made it into the registry, releasing its context and slot.
+async fn discard_tab(tab: Tab) -> Result<(), Error> {
+  let crashed = tab.is_crashed();
+  let closed = close_page(tab.page, crashed).await;
+  let disposed = dispose_context(tab.context).await;
+  closed.and(disposed)
+}
+/// Passes an unregistered tab on with the result of a setup step, or discards the tab when
+/// the step failed. The step's error is returned; failing to discard the tab is only logged.
+async fn discard_on_error<T>(tab: Tab, result: Result<T, Error>) -> Result<(Tab, T), Error> {
+  match result {
+    Ok(value) => Ok((tab, value)),
+    Err(e) => {
+      if let Err(discard_error) = discard_tab(tab).await {
+        tracing::warn!("Failed to discard a tab that failed to open: {discard_error}");
+      }
+      Err(e)
+    }
+  }
+}
+/// Resolves a tab of the registry that is still usable, counting as activity.
+fn live_tab<'a>(tabs: &'a mut HashMap<String, Tab>, tab_id: &str) -> Result<&'a mut Tab, Error> {
+  let tab = tabs
+    .get_mut(tab_id)
+    .ok_or_else(|| Error::NotFound(format!("tab_id {tab_id}")))?;
+  if tab.is_crashed() {
+    return Err(Error::Crashed(format!(
+      "tab_id {tab_id} crashed; only its errors can be read and it can be closed"
+    )));
+  }
+
+  tab.last_activity = SystemTime::now();
+  Ok(tab)
+}
+/// A page action running on a tab.
+///
+/// Keeps the tab from going idle until dropped; the end of the action then counts as
+/// activity and the tab's expiration is rescheduled accordingly.
+struct ActionGuard {
+  tab_id: String,
+  dialogs: Option<DialogWatch>,
+}
+
+impl Drop for ActionGuard {
+  fn drop(&mut self) {
+    async fn finish(tab_id: String) {
+      let due_at = TABS.lock().await.get_mut(&tab_id).map(|tab| {
+        tab.in_flight = tab.in_flight.saturating_sub(1);
+        tab.last_activity = SystemTime::now();
+        tab.due_at()
+      });
+      if let Some(due_at) = due_at {
+        reaper::schedule(&tab_id, due_at).await;
+      }
+    }
+
+    tokio::spawn(finish(std::mem::take(&mut self.tab_id)));
+  }
+}
+
+/// Resolves a tab for a page action, counting as activity until the action ends.
+async fn find_guarded(tab_id: &str) -> Result<(Arc<ChaserPage>, ActionGuard), Error> {
+  live_tab(&mut *TABS.lock().await, tab_id).map(|tab| {
+    tab.in_flight += 1;
+    let guard = ActionGuard {
+      tab_id: tab_id.to_string(),
+      dialogs: tab.dialogs.as_ref().map(DialogHandler::watch),
+    };
+    (tab.page.clone(), guard)
+  })
+}
+/// Runs a page action, failing fast while a pending dialog blocks the page.
+async fn unblocked<T>(
+  mut guard: ActionGuard,
+  action: impl Future<Output = Result<T, Error>>,
+) -> Result<T, Error> {
+  match guard.dialogs.take() {
+    Some(dialogs) => dialogs.guard(action).await,
+    None => action.await,
+  }
+}
+/// Resolves a tab with its request interception state, counting as activity.
+async fn find_interception(
+  tab_id: &str,
+) -> Result<(Arc<ChaserPage>, Option<Arc<Interception>>), Error> {
+  live_tab(&mut *TABS.lock().await, tab_id).map(|tab| {
+    let interception = tab
+      .interceptor
+      .as_ref()
+      .map(|interceptor| interceptor.interception.clone());
+    (tab.page.clone(), interception)
+  })
+}
+/// Sets the extra HTTP headers of the page, replacing the previous ones.
+async fn set_extra_headers(
+  chaser: &ChaserPage,
+  headers: HashMap<String, String>,
+) -> Result<(), Error> {
+  let headers = headers
+    .into_iter()
+    .map(|(name, value)| (name, serde_json::Value::String(value)))
+    .collect::<serde_json::Map<_, _>>();
+
+  chaser
+    .raw_page()
+    .execute(SetExtraHttpHeadersParams::new(Headers::new(headers)))
+    .await
+    .map(|_| ())
+    .map_err(|e| {
+      Error::Operation(ErrorInfo {
+        message: format!("Failed to set extra HTTP headers: {e}"),
+        code: None,
+      })
+    })
+}
+/// Returns the request interception state of a tab, starting the interception if needed.
+///
+/// The interception is started outside the registry lock; if another call started one
+/// for the same tab in the meantime, that one is kept.
+async fn ensure_interception(tab_id: &str) -> Result<Arc<Interception>, Error> {
+  let (page, interception) = find_interception(tab_id).await?;
+  if let Some(interception) = interception {
+    return Ok(interception);
+  }
+
+  let interceptor = network::intercept(
+    page.raw_page(),
+    Interception::new(None, None, BlockRules::default()),
+  )
+  .await?;
+
+  live_tab(&mut *TABS.lock().await, tab_id).map(|tab| {
+    tab
+      .interceptor
+      .get_or_insert(interceptor)
+      .interception
+      .clone()
+  })
+}
+/// Applies a stealth profile to the page before its first navigation.
+///
+/// The OS preset (or the server default) is applied through `ChaserProfile`; custom
+/// fields the preset cannot express, and the languages derived from `region`, are
+/// layered on top with a user agent override and a script that runs after the profile's
+/// bootstrap script.
+async fn apply_profile(
+  chaser: &ChaserPage,
+  profile: Option<ProfileDto>,
+  region: &RegionDto,
+) -> Result<(), Error> {
+  fn profile_error(e: impl std::fmt::Display) -> Error {
+    Error::Operation(ErrorInfo {
+      message: format!("Failed to apply stealth profile: {e}"),
+      code: None,
+    })
+  }
+  fn preset(os: OsDto) -> ChaserProfileBuilder {
+    match os {
+      OsDto::Windows => ChaserProfile::windows(),
+      OsDto::Macos => ChaserProfile::macos_arm(),
+      OsDto::Linux => ChaserProfile::linux(),
+    }
+  }
+  fn parse_languages(accept_language: &str) -> Vec<String> {
+    accept_language
+      .split(',')
+      .filter_map(|language| language.split(';').next())
+      .map(str::trim)
+      .filter(|language| !language.is_empty())
+      .map(str::to_string)
+      .collect()
+  }
+
+  let (os, custom) = match profile {
+    None => (STEALTH.os, CustomProfileDto::default()),
+    Some(ProfileDto::Os(os)) => (os, CustomProfileDto::default()),
+    Some(ProfileDto::Custom(custom)) => (custom.os.unwrap_or(STEALTH.os), custom),
+  };
+
+  let mut builder = preset(os);
+  if let Some(version) = custom.chrome_version {
+    builder = builder.chrome_version(version);
+  }
+  if let Some(cores) = custom.hardware_concurrency {
+    builder = builder.cpu_cores(cores);
+  }
+  if let Some(memory) = custom.device_memory {
+    builder = builder.memory_gb(memory);
+  }
+  if let Some(locale) = &region.locale {
+    builder = builder.locale(locale.clone());
+  }
+  if let Some(timezone_id) = &region.timezone_id {
+    builder = builder.timezone(timezone_id.clone());
+  }
+  let profile = builder.build();
+
+  chaser
+    .apply_profile(&profile)
+    .await
+    .map_err(profile_error)?;
+
+  // The Accept-Language header and `navigator.languages` must tell the same story.
+  let accept_language = region
+    .accept_language
+    .clone()
+    .or_else(|| {
+      custom
+        .languages
+        .as_ref()
+        .map(|languages| languages.join(","))
+    })
+    .or_else(|| region.locale.clone());
+  let languages = custom
+    .languages
+    .or_else(|| accept_language.as_deref().map(parse_languages));
+
+  let page = chaser.raw_page();
+
+  if custom.user_agent.is_some() || custom.platform.is_some() || accept_language.is_some() {
+    let user_agent = SetUserAgentOverrideParams {
+      accept_language,
+      platform: custom.platform.clone(),
+      ..SetUserAgentOverrideParams::new(custom.user_agent.unwrap_or_else(|| profile.user_agent()))
+    };
+    page.execute(user_agent).await.map_err(profile_error)?;
+  }
+
+  if custom.platform.is_none()
+    && languages.is_none()
+    && custom.webgl_vendor.is_none()
+    && custom.webgl_renderer.is_none()
+  {
+    return Ok(());
+  }
+
+  let overrides = serde_json::json!({
+    "platform": custom.platform,
+    "languages": languages,
+    "webglVendor": custom.webgl_vendor,
+    "webglRenderer": custom.webgl_renderer,
+  });
+  let script = format!(
+    r"(() => {{
+      const overrides = {overrides};
+      const define = (name, get) => Object.defineProperty(Navigator.prototype, name, {{ get, configurable: true }});
+      if (overrides.platform) define('platform', () => overrides.platform);
+      if (overrides.languages && overrides.languages.length) {{
+        define('languages', () => Object.freeze([...overrides.languages]));
+        define('language', () => overrides.languages[0]);
+      }}
+      if (overrides.webglVendor || overrides.webglRenderer) {{
+        const spoofWebGL = (proto) => {{
+          const getParameter = proto.getParameter;
+          proto.getParameter = function(parameter) {{
+            if (parameter === 37445 && overrides.webglVendor) return overrides.webglVendor;
+            if (parameter === 37446 && overrides.webglRenderer) return overrides.webglRenderer;
+            return getParameter.apply(this, arguments);
+          }};
+        }};
+        spoofWebGL(WebGLRenderingContext.prototype);
+        if (typeof WebGL2RenderingContext !== 'undefined') spoofWebGL(WebGL2RenderingContext.prototype);
+      }}
+    }})();"
+  );
+
+  page
+    .execute(AddScriptToEvaluateOnNewDocumentParams::new(script))
+    .await
+    .map(|_| ())
+    .map_err(profile_error)
+}
+/// Overrides the locale, timezone and geolocation of the page.
+///
+/// Geolocation also grants the geolocation permission in the tab's browser context, so
+/// pages can read the position without a prompt; the grant goes away with the context.
+/// `open` rejects geolocation for the shared profile, where the grant would outlive the tab.
+async fn emulate_region(
+  chaser: &ChaserPage,
+  context_id: Option<BrowserContextId>,
+  region: &RegionDto,
+) -> Result<(), Error> {
+  fn emulation_error(e: impl std::fmt::Display) -> Error {
+    Error::Operation(ErrorInfo {
+      message: format!("Failed to emulate region: {e}"),
+      code: None,
+    })
+  }
+
+  let page = chaser.raw_page();
+
+  if let Some(locale) = &region.locale {
+    let params = SetLocaleOverrideParams {
+      locale: Some(locale.clone()),
+    };
+    page.execute(params).await.map_err(emulation_error)?;
+  }
+  if let Some(timezone_id) = &region.timezone_id {
+    page
+      .execute(SetTimezoneOverrideParams::new(timezone_id.clone()))
+      .await
+      .map_err(emulation_error)?;
+  }
+  if let Some(geolocation) = &region.geolocation {
+    let permissions = GrantPermissionsParams {
+      browser_context_id: context_id,
+      ..GrantPermissionsParams::new(vec![PermissionType::Geolocation])
+    };
+    let position = SetGeolocationOverrideParams {
+      latitude: Some(geolocation.latitude),
+      longitude: Some(geolocation.longitude),
+      accuracy: Some(geolocation.accuracy.unwrap_or(100.0)),
+      ..SetGeolocationOverrideParams::default()
+    };
+    page.execute(permissions).await.map_err(emulation_error)?;
+    page.execute(position).await.map_err(emulation_error)?;
+  }
+
+  Ok(())
+}
+/// Overrides the viewport metrics and touch support of the page.
+async fn emulate_viewport(chaser: &ChaserPage, viewport: &ViewportDto) -> Result<(), Error> {
+  fn emulation_error(e: impl std::fmt::Display) -> Error {
+    Error::Operation(ErrorInfo {
+      message: format!("Failed to emulate viewport: {e}"),
+      code: None,
+    })
+  }
+
+  let width = i64::from(viewport.width);
+  let height = i64::from(viewport.height);
+  let metrics = SetDeviceMetricsOverrideParams {
+    screen_width: Some(width),
+    screen_height: Some(height),
+    ..SetDeviceMetricsOverrideParams::new(
+      width,
+      height,
+      viewport.device_scale_factor.unwrap_or(1.0),
+      viewport.is_mobile,
+    )
+  };
+  let touch = SetTouchEmulationEnabledParams {
+    max_touch_points: viewport.has_touch.then_some(5),
+    ..SetTouchEmulationEnabledParams::new(viewport.has_touch)
+  };
+
+  let page = chaser.raw_page();
+  page.execute(metrics).await.map_err(emulation_error)?;
+  page
+    .execute(touch)
+    .await
+    .map(|_| ())
+    .map_err(emulation_error)
+}
+/// Sets the cookies of a storage state and seeds its localStorage on the matching origins.
+///
+/// localStorage can only be written from a document of the origin, so it is seeded by a
+/// script that runs on every new document and writes the items once per origin and tab.
+async fn load_storage_state(chaser: &Arc<ChaserPage>, state: StorageStateDto) -> Result<(), Error> {
+  let cookies = state
+    .cookies
+    .into_iter()
+    .map(|cookie| CookieParam {
+      domain: Some(cookie.domain),
+      path: Some(cookie.path),
+      secure: Some(cookie.secure),
+      http_only: Some(cookie.http_only),
+      same_site: Some(cookie.same_site),
+      expires: (cookie.expires > 0.0).then(|| TimeSinceEpoch::new(cookie.expires)),
+      ..CookieParam::new(cookie.name, cookie.value)
+    })
+    .collect::<Vec<_>>();
+
+  if !cookies.is_empty() {
+    chaser
+      .raw_page()
+      .execute(SetCookiesParams::new(cookies))
+      .await
+      .map_err(|e| {
+        Error::Operation(ErrorInfo {
+          message: format!("Failed to set storage state cookies: {e}"),
+          code: None,
+        })
+      })?;
+  }
+
+  let origins = state
+    .origins
+    .into_iter()
+    .map(|origin| {
+      let items = origin
+        .local_storage
+        .into_iter()
+        .map(|item| (item.name, item.value))
+        .collect::<Vec<_>>();
+      (origin.origin, items)
+    })
+    .collect::<HashMap<_, _>>();
+
+  if origins.is_empty() {
+    return Ok(());
+  }
+
+  let origins = serde_json::to_string(&origins).map_err(|e| {
+    Error::Operation(ErrorInfo {
+      message: format!("Failed to serialize storage state: {e}"),
+      code: None,
+    })
+  })?;
+
+  let script = format!(
+    r"(() => {{
+      const items = {origins}[location.origin];
+      if (!items) return;
+      try {{
+        if (sessionStorage.getItem('__storage_state_seeded')) return;
+        sessionStorage.setItem('__storage_state_seeded', '1');
+        for (const [name, value] of items) localStorage.setItem(name, value);
+      }} catch (_) {{}}
+    }})();"
+  );
+
+  chaser
+    .raw_page()
+    .execute(AddScriptToEvaluateOnNewDocumentParams::new(script))
+    .await
+    .map(|_| ())
+    .map_err(|e| {
+      Error::Operation(ErrorInfo {
+        message: format!("Failed to preload localStorage: {e}"),
+        code: None,
+      })
+    })
+}
+async fn read_cookies(chaser: Arc<ChaserPage>) -> Result<Vec<Cookie>, Error> {
+  chaser.raw_page().get_cookies().await.map_err(|e| {
+    Error::Operation(ErrorInfo {
+      message: format!("Failed to get cookies: {e}"),
+      code: None,
+    })
+  })
+}
+fn to_delete_params(cookies: &[Cookie]) -> Vec<DeleteCookiesParams> {
+  cookies
+    .iter()
+    .map(|cookie| {
+      DeleteCookiesParams::builder()
+        .name(cookie.name.clone())
+        .domain(cookie.domain.clone())
+        .path(cookie.path.clone())
+        .build()
+        .unwrap_or_else(|_| DeleteCookiesParams::new(cookie.name.clone()))
+    })
+    .collect()
 }
-async fn close_page(chaser: Arc<ChaserPage>) -> Result<(), Error> {
-  chaser.raw_page().clone().close().await.map_err(|e| {
+/// Closes the page; a crashed renderer no longer answers `Page.close`, so its target is
+/// closed through the `Target` domain instead.
+async fn close_page(chaser: Arc<ChaserPage>, crashed: bool) -> Result<(), Error> {
+  let page = chaser.raw_page().clone();
+  let closed = if crashed {
+    page
+      .execute(CloseTargetParams::new(page.target_id().clone()))
+      .await
+      .map(|_| ())
+  } else {
+    page.close().await
+  };
+
+  closed.map_err(|e| {
     Error::Operation(ErrorInfo {
       message: format!("Failed to close tab: {e}"),
       code: None,
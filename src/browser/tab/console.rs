@@ -0,0 +1,163 @@
+use chaser_oxide::Page;
+use chaser_oxide::cdp::browser_protocol::log::{EventEntryAdded, LogEntryLevel};
+use chaser_oxide::cdp::js_protocol::runtime::{
+  ConsoleApiCalledType, EnableParams, EventConsoleApiCalled, RemoteObject,
+};
+use futures::stream::{self, StreamExt};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::browser::tab::dto::{ConsoleLevelDto, ConsoleMessageDto, ConsoleQueryDto};
+use crate::browser::tab::models::TabTask;
+use crate::models::{Error, ErrorInfo};
+
+/// Messages kept per tab; the oldest message is dropped for every new one beyond this.
+const MAX_MESSAGES: usize = 1_000;
+
+/// The console capture of a tab; stops when dropped together with the tab record.
+pub struct ConsoleRecorder {
+  messages: Arc<Mutex<VecDeque<ConsoleMessageDto>>>,
+  _task: TabTask,
+}
+
+impl ConsoleRecorder {
+  /// Returns the buffered messages matching the query, oldest first.
+  #[must_use]
+  pub fn messages(&self, query: &ConsoleQueryDto) -> Vec<ConsoleMessageDto> {
+    self
+      .messages
+      .lock()
+      .unwrap_or_else(PoisonError::into_inner)
+      .iter()
+      .filter(|message| query.level.is_none_or(|level| message.level >= level))
+      .filter(|message| query.since.is_none_or(|since| message.timestamp > since))
+      .cloned()
+      .collect()
+  }
+}
+
+/// Starts capturing the console messages of a page.
+///
+/// # Behavior
+///
+/// - Enables the `Runtime` domain and listens to `Runtime.consoleAPICalled` (the page's
+///   `console.*` calls) and `Log.entryAdded` (browser messages such as network errors,
+///   deprecations and interventions).
+/// - Joins the arguments of a `console.*` call into the message text and takes the source
+///   location from the top frame of its stack trace.
+/// - Keeps the latest 1 000 messages per tab in a ring buffer.
+///
+/// The capture runs as a background task that is aborted when the returned
+/// `ConsoleRecorder` is dropped.
+///
+/// # Errors
+///
+/// Returns an `Error` if subscribing to the events or enabling the `Runtime` domain fails.
+///
+/// # Examples
+///
+/// ```ignore
+/// let recorder = console::record(chaser.raw_page()).await?;
+/// ```
+pub async fn record(page: &Page) -> Result<ConsoleRecorder, Error> {
+  fn capture_error(e: impl std::fmt::Display) -> Error {
+    Error::Operation(ErrorInfo {
+      message: format!("Failed to capture console messages: {e}"),
+      code: None,
+    })
+  }
+  fn describe(arg: &RemoteObject) -> String {
+    match (&arg.value, &arg.unserializable_value, &arg.description) {
+      (Some(serde_json::Value::String(value)), _, _) => value.clone(),
+      (Some(value), _, _) => value.to_string(),
+      (None, Some(value), _) => value.inner().clone(),
+      (None, None, Some(description)) => description.clone(),
+      (None, None, None) => arg.r#type.as_ref().to_string(),
+    }
+  }
+  fn from_console_api(event: &EventConsoleApiCalled) -> ConsoleMessageDto {
+    let level = match event.r#type {
+      ConsoleApiCalledType::Debug => ConsoleLevelDto::Debug,
+      ConsoleApiCalledType::Warning => ConsoleLevelDto::Warning,
+      ConsoleApiCalledType::Error | ConsoleApiCalledType::Assert => ConsoleLevelDto::Error,
+      _ => ConsoleLevelDto::Info,
+    };
+    let frame = event
+      .stack_trace
+      .as_ref()
+      .and_then(|trace| trace.call_frames.first());
+
+    ConsoleMessageDto {
+      level,
+      source: "console-api".to_string(),
+      text: event
+        .args
+        .iter()
+        .map(describe)
+        .collect::<Vec<_>>()
+        .join(" "),
+      url: frame
+        .map(|frame| frame.url.clone())
+        .filter(|url| !url.is_empty()),
+      line: frame.map(|frame| frame.line_number),
+      timestamp: to_millis(*event.timestamp.inner()),
+    }
+  }
+  fn from_log(event: &EventEntryAdded) -> ConsoleMessageDto {
+    let entry = &event.entry;
+    let level = match entry.level {
+      LogEntryLevel::Verbose => ConsoleLevelDto::Debug,
+      LogEntryLevel::Info => ConsoleLevelDto::Info,
+      LogEntryLevel::Warning => ConsoleLevelDto::Warning,
+      LogEntryLevel::Error => ConsoleLevelDto::Error,
+    };
+
+    ConsoleMessageDto {
+      level,
+      source: entry.source.as_ref().to_string(),
+      text: entry.text.clone(),
+      url: entry.url.clone(),
+      line: entry.line_number,
+      timestamp: to_millis(*entry.timestamp.inner()),
+    }
+  }
+  #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+  fn to_millis(timestamp: f64) -> u64 {
+    timestamp.max(0.0) as u64
+  }
+
+  let console = page
+    .event_listener::<EventConsoleApiCalled>()
+    .await
+    .map_err(capture_error)?;
+  let log = page
+    .event_listener::<EventEntryAdded>()
+    .await
+    .map_err(capture_error)?;
+  page
+    .execute(EnableParams::default())
+    .await
+    .map_err(capture_error)?;
+
+  let messages = Arc::new(Mutex::new(VecDeque::with_capacity(MAX_MESSAGES)));
+  let buffer = messages.clone();
+  let task = tokio::spawn(async move {
+    let mut events = stream::select(
+      console.map(|event| from_console_api(&event)),
+      log.map(|event| from_log(&event)),
+    );
+
+    while let Some(message) = events.next().await {
+      let mut buffer = buffer.lock().unwrap_or_else(PoisonError::into_inner);
+      if buffer.len() >= MAX_MESSAGES {
+        buffer.pop_front();
+      }
+      buffer.push_back(message);
+    }
+  });
+
+  Ok(ConsoleRecorder {
+    messages,
+    _task: TabTask::new(task.abort_handle()),
+  })
+}
@@ -0,0 +1,253 @@
+use chaser_oxide::Page;
+use chaser_oxide::cdp::browser_protocol::page::{
+  EventJavascriptDialogClosed, EventJavascriptDialogOpening, FrameId, HandleJavaScriptDialogParams,
+};
+use futures::future;
+use futures::stream::{self, StreamExt};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::SystemTime;
+use tokio::sync::watch;
+use uuid::Uuid;
+
+use crate::browser::tab::dto::{
+  DialogActionDto, DialogClosedDto, DialogDto, DialogPolicyDto, DialogsDto,
+};
+use crate::browser::tab::models::{TabTask, to_unix_millis};
+use crate::models::{Error, ErrorInfo};
+
+/// Closed dialogs kept per tab; the oldest dialog is dropped for every new one beyond this.
+const MAX_HISTORY: usize = 100;
+
+/// A dialog waiting for an answer, with the frame whose `javascriptDialogClosed` closes it.
+struct Pending {
+  frame_id: FrameId,
+  dialog: DialogDto,
+}
+
+#[derive(Default)]
+struct DialogState {
+  pending: Vec<Pending>,
+  history: VecDeque<DialogDto>,
+}
+
+/// The dialog handling of a tab; stops when dropped together with the tab record.
+pub struct DialogHandler {
+  state: Arc<Mutex<DialogState>>,
+  /// The oldest dialog left pending by the `manual` policy.
+  blocking: watch::Receiver<Option<DialogDto>>,
+  _task: TabTask,
+}
+
+impl DialogHandler {
+  /// Returns the pending dialogs and the history of closed ones.
+  #[must_use]
+  pub fn describe(&self) -> DialogsDto {
+    let state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+    DialogsDto {
+      pending: state
+        .pending
+        .iter()
+        .map(|pending| pending.dialog.clone())
+        .collect(),
+      history: state.history.iter().cloned().collect(),
+    }
+  }
+
+  /// Whether the dialog with the given ID waits for an answer.
+  #[must_use]
+  pub fn is_pending(&self, dialog_id: &str) -> bool {
+    self
+      .state
+      .lock()
+      .unwrap_or_else(PoisonError::into_inner)
+      .pending
+      .iter()
+      .any(|pending| pending.dialog.dialog_id == dialog_id)
+  }
+
+  /// Returns a watch of the dialogs that block the page.
+  #[must_use]
+  pub fn watch(&self) -> DialogWatch {
+    DialogWatch(self.blocking.clone())
+  }
+}
+
+/// Watches the dialogs of a tab that wait for an answer through the API.
+pub struct DialogWatch(watch::Receiver<Option<DialogDto>>);
+
+impl DialogWatch {
+  /// Runs a page action unless a pending dialog blocks the page.
+  ///
+  /// # Errors
+  ///
+  /// Returns `Error::Blocked` when a dialog is pending before the action starts or opens
+  /// while it runs; the action is abandoned then, as the page would not answer it until
+  /// the dialog is closed. Otherwise returns the result of the action.
+  pub async fn guard<T>(
+    mut self,
+    action: impl Future<Output = Result<T, Error>>,
+  ) -> Result<T, Error> {
+    fn blocked(dialog: &DialogDto) -> Error {
+      Error::Blocked(format!(
+        "a {} dialog blocks the page ({:?}); answer dialog_id {} first",
+        dialog.kind, dialog.message, dialog.dialog_id
+      ))
+    }
+
+    if let Some(dialog) = self.0.borrow_and_update().as_ref() {
+      return Err(blocked(dialog));
+    }
+
+    let opened = async {
+      let error = self
+        .0
+        .wait_for(Option::is_some)
+        .await
+        .ok()
+        .and_then(|dialog| dialog.as_ref().map(blocked));
+      // Without dialog handling nothing can block the action.
+      match error {
+        Some(error) => error,
+        None => future::pending().await,
+      }
+    };
+
+    tokio::select! {
+      result = action => result,
+      error = opened => Err(error),
+    }
+  }
+}
+
+/// Starts handling the JavaScript dialogs of a page.
+///
+/// # Behavior
+///
+/// - Listens to `Page.javascriptDialogOpening` and `Page.javascriptDialogClosed`.
+/// - Accepts (with `policy.prompt_text` or the default prompt text) or dismisses every
+///   dialog right away according to `policy.action`; with `manual` the dialog stays pending
+///   until it is answered with `Page.handleJavaScriptDialog`, and page actions guarded by
+///   `DialogWatch::guard` fail fast meanwhile.
+/// - Moves closed dialogs with their outcome into a history of the latest 100 dialogs.
+///
+/// The handling runs as a background task that is aborted when the returned
+/// `DialogHandler` is dropped.
+///
+/// # Errors
+///
+/// Returns an `Error` if subscribing to the dialog events fails.
+///
+/// # Examples
+///
+/// ```ignore
+/// let handler = dialogs::handle(chaser.raw_page(), DialogPolicyDto::default()).await?;
+/// ```
+pub async fn handle(page: &Page, policy: DialogPolicyDto) -> Result<DialogHandler, Error> {
+  enum Event {
+    Opening(Arc<EventJavascriptDialogOpening>),
+    Closed(Arc<EventJavascriptDialogClosed>),
+  }
+
+  fn handling_error(e: impl std::fmt::Display) -> Error {
+    Error::Operation(ErrorInfo {
+      message: format!("Failed to handle dialogs: {e}"),
+      code: None,
+    })
+  }
+  fn on_opening(state: &mut DialogState, event: &EventJavascriptDialogOpening) {
+    state.pending.push(Pending {
+      frame_id: event.frame_id.clone(),
+      dialog: DialogDto {
+        dialog_id: Uuid::new_v4().to_string(),
+        kind: event.r#type.as_ref().to_string(),
+        message: event.message.clone(),
+        url: event.url.clone(),
+        default_prompt: event.default_prompt.clone(),
+        opened_at: to_unix_millis(SystemTime::now()),
+        closed: None,
+      },
+    });
+  }
+  fn on_closed(state: &mut DialogState, event: &EventJavascriptDialogClosed) {
+    let Some(index) = state
+      .pending
+      .iter()
+      .position(|pending| pending.frame_id == event.frame_id)
+    else {
+      return;
+    };
+
+    let mut dialog = state.pending.remove(index).dialog;
+    dialog.closed = Some(DialogClosedDto {
+      accepted: event.result,
+      user_input: event.user_input.clone(),
+      closed_at: to_unix_millis(SystemTime::now()),
+    });
+    if state.history.len() >= MAX_HISTORY {
+      state.history.pop_front();
+    }
+    state.history.push_back(dialog);
+  }
+
+  let opening = page
+    .event_listener::<EventJavascriptDialogOpening>()
+    .await
+    .map_err(handling_error)?;
+  let closed = page
+    .event_listener::<EventJavascriptDialogClosed>()
+    .await
+    .map_err(handling_error)?;
+
+  let state = Arc::new(Mutex::new(DialogState::default()));
+  let (blocking, watched) = watch::channel(None);
+  let handled = state.clone();
+  let page = page.clone();
+  let task = tokio::spawn(async move {
+    let mut events = stream::select(opening.map(Event::Opening), closed.map(Event::Closed));
+
+    while let Some(event) = events.next().await {
+      let opened = {
+        let mut state = handled.lock().unwrap_or_else(PoisonError::into_inner);
+        let opened = match event {
+          Event::Opening(event) => {
+            on_opening(&mut state, &event);
+            Some(event)
+          }
+          Event::Closed(event) => {
+            on_closed(&mut state, &event);
+            None
+          }
+        };
+        // Dialogs answered by the policy block the page only for a moment.
+        if matches!(policy.action, DialogActionDto::Manual) {
+          blocking.send_replace(state.pending.first().map(|pending| pending.dialog.clone()));
+        }
+        opened
+      };
+
+      let answer = opened.and_then(|event| match policy.action {
+        DialogActionDto::Manual => None,
+        DialogActionDto::Accept => Some(HandleJavaScriptDialogParams {
+          prompt_text: policy
+            .prompt_text
+            .clone()
+            .or_else(|| event.default_prompt.clone()),
+          ..HandleJavaScriptDialogParams::new(true)
+        }),
+        DialogActionDto::Dismiss => Some(HandleJavaScriptDialogParams::new(false)),
+      });
+      if let Some(params) = answer
+        && let Err(e) = page.execute(params).await
+      {
+        tracing::debug!("Failed to answer a dialog: {e}");
+      }
+    }
+  });
+
+  Ok(DialogHandler {
+    state,
+    blocking: watched,
+    _task: TabTask::new(task.abort_handle()),
+  })
+}
@@ -1,21 +1,425 @@
-use serde::Deserialize;
+use chaser_oxide::auth::Credentials;
+use chaser_oxide::cdp::browser_protocol::network::CookieSameSite;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
 
 #[derive(Deserialize)]
 pub struct OpenDto {
   pub url: String,
   #[serde(default = "default_expiration")]
   pub expiration: u64,
+  pub idle_timeout: Option<u64>,
+  #[serde(default)]
+  pub isolated: bool,
+  pub session_id: Option<String>,
+  /// Proxy the tab egresses through, in a browser context of its own.
+  pub proxy: Option<ProxyDto>,
+  /// Draw the proxy from the configured pool instead of passing `proxy`.
+  pub proxy_pool: Option<ProxyPoolDto>,
+  /// Extra HTTP headers sent with every request of the tab.
+  #[serde(default)]
+  pub headers: HashMap<String, String>,
+  /// Credentials answered to the auth challenges of websites (HTTP Basic/Digest).
+  pub http_credentials: Option<HttpCredentialsDto>,
+  /// Requests to block for the lifetime of the tab.
+  pub block: Option<BlockDto>,
+  /// Record the network traffic of the tab as HAR.
+  pub har: Option<HarOptionsDto>,
+  /// Capture the console messages of the tab; enables the `Runtime` domain, which pages
+  /// can detect.
+  #[serde(default)]
+  pub console: bool,
+  /// Record the uncaught exceptions of the tab; enables the `Runtime` domain like `console`,
+  /// which records them as well.
+  #[serde(default)]
+  pub errors: bool,
+  /// How JavaScript dialogs of the tab are answered.
+  #[serde(default)]
+  pub dialog: DialogPolicyDto,
+  /// Cookies and localStorage to preload before navigation.
+  pub storage_state: Option<StorageStateDto>,
+  #[serde(default)]
+  pub wait_until: WaitUntilDto,
+  #[serde(default = "default_timeout_ms")]
+  pub timeout_ms: u64,
+  pub referrer: Option<String>,
+  /// Closes the tab and fails when the main document responds with 4xx/5xx.
+  #[serde(default)]
+  pub fail_on_status: bool,
+  pub viewport: Option<ViewportDto>,
+  /// Named device preset; cannot be combined with `viewport`.
+  pub device: Option<DeviceDto>,
+  /// Stealth profile; the server default (`STEALTH_PROFILE`) when omitted.
+  pub profile: Option<ProfileDto>,
+  #[serde(flatten)]
+  pub region: RegionDto,
 }
 
 fn default_expiration() -> u64 {
   30 // default expiration time in seconds
 }
 
+pub(crate) fn default_timeout_ms() -> u64 {
+  30_000 // default navigation timeout in milliseconds
+}
+
+/// Clamps a navigation timeout to 1..=300000 milliseconds.
+#[must_use]
+pub(crate) fn bounded_timeout(timeout_ms: u64) -> Duration {
+  Duration::from_millis(timeout_ms.clamp(1, 300_000))
+}
+
+#[derive(Deserialize)]
+pub struct ViewportDto {
+  pub width: u32,
+  pub height: u32,
+  pub device_scale_factor: Option<f64>,
+  #[serde(default)]
+  pub is_mobile: bool,
+  #[serde(default)]
+  pub has_touch: bool,
+}
+
+/// Viewport presets of common devices.
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceDto {
+  /// iPhone 14: 390x844 at 3x, mobile with touch.
+  Iphone,
+  /// Pixel 7: 412x915 at 2.625x, mobile with touch.
+  Pixel,
+  /// iPad Air: 820x1180 at 2x, mobile with touch.
+  Ipad,
+  /// Desktop HD: 1920x1080 at 1x.
+  DesktopHd,
+}
+
+impl DeviceDto {
+  #[must_use]
+  pub(crate) fn viewport(&self) -> ViewportDto {
+    let (width, height, device_scale_factor, is_mobile) = match self {
+      DeviceDto::Iphone => (390, 844, 3.0, true),
+      DeviceDto::Pixel => (412, 915, 2.625, true),
+      DeviceDto::Ipad => (820, 1180, 2.0, true),
+      DeviceDto::DesktopHd => (1920, 1080, 1.0, false),
+    };
+
+    ViewportDto {
+      width,
+      height,
+      device_scale_factor: Some(device_scale_factor),
+      is_mobile,
+      has_touch: is_mobile,
+    }
+  }
+}
+
+/// Operating system impersonated by a stealth profile.
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum OsDto {
+  Windows,
+  Macos,
+  Linux,
+}
+
+/// A stealth profile: an OS preset name or custom fields on top of a preset.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum ProfileDto {
+  Os(OsDto),
+  Custom(CustomProfileDto),
+}
+
+#[derive(Deserialize, Default)]
+pub struct CustomProfileDto {
+  /// Preset the custom fields are applied on top of; the server default when omitted.
+  pub os: Option<OsDto>,
+  pub user_agent: Option<String>,
+  pub platform: Option<String>,
+  pub chrome_version: Option<u32>,
+  pub hardware_concurrency: Option<u32>,
+  pub device_memory: Option<u32>,
+  pub languages: Option<Vec<String>>,
+  pub webgl_vendor: Option<String>,
+  pub webgl_renderer: Option<String>,
+}
+
+/// An upstream proxy for a browser context.
+#[derive(Deserialize, Clone)]
+pub struct ProxyDto {
+  /// Proxy URL, e.g. `http://proxy.example.com:8080` or `socks5://10.0.0.1:1080`.
+  pub server: String,
+  pub username: Option<String>,
+  pub password: Option<String>,
+  /// Comma-separated hosts that bypass the proxy, e.g. `localhost,*.internal`.
+  pub bypass: Option<String>,
+}
+
+impl ProxyDto {
+  /// Credentials answered to the proxy's auth challenges, if a username is set.
+  #[must_use]
+  pub fn credentials(&self) -> Option<Credentials> {
+    self.username.clone().map(|username| Credentials {
+      username,
+      password: self.password.clone().unwrap_or_default(),
+    })
+  }
+}
+
+/// How a proxy is drawn from the pool.
+#[derive(Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PoolStrategyDto {
+  #[default]
+  RoundRobin,
+  LeastUsed,
+  Sticky,
+}
+
+#[derive(Deserialize, Default)]
+pub struct ProxyPoolDto {
+  /// Overrides the strategy of the pool configuration.
+  pub strategy: Option<PoolStrategyDto>,
+  /// Key that keeps getting the same proxy with the `sticky` strategy, e.g. a customer ID.
+  pub sticky_key: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct HttpCredentialsDto {
+  pub username: String,
+  #[serde(default)]
+  pub password: String,
+}
+
+impl From<HttpCredentialsDto> for Credentials {
+  fn from(dto: HttpCredentialsDto) -> Self {
+    Credentials {
+      username: dto.username,
+      password: dto.password,
+    }
+  }
+}
+
+#[derive(Deserialize)]
+pub struct HeadersDto {
+  /// Replaces the extra HTTP headers of the tab; an empty map removes them.
+  pub headers: HashMap<String, String>,
+}
+
+/// Requests a tab blocks: by resource type or by URL.
+#[derive(Deserialize, Serialize, Clone, Default)]
+pub struct BlockDto {
+  /// CDP resource types, e.g. `image`, `font`, `media`, `stylesheet`.
+  #[serde(default)]
+  pub resource_types: Vec<String>,
+  /// Globs matched against the whole URL, e.g. `*.doubleclick.net/*`.
+  #[serde(default)]
+  pub url_patterns: Vec<String>,
+  /// Regular expressions matched anywhere in the URL.
+  #[serde(default)]
+  pub url_regexes: Vec<String>,
+}
+
+/// A route rule: requests matching `url` and `method` are handled by `action`.
+#[derive(Deserialize)]
+pub struct RouteDto {
+  /// Glob matched against the whole URL.
+  pub url: String,
+  /// HTTP method; any method when omitted.
+  pub method: Option<String>,
+  #[serde(flatten)]
+  pub action: RouteActionDto,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum RouteActionDto {
+  /// Respond with a canned response without hitting the network.
+  Fulfill {
+    #[serde(default = "default_route_status")]
+    status: i64,
+    #[serde(default)]
+    headers: HashMap<String, String>,
+    #[serde(default)]
+    body: String,
+    /// `body` is base64-encoded binary data.
+    #[serde(default)]
+    body_base64: bool,
+  },
+  /// Fail the request with a CDP network error reason (`Failed` by default).
+  Abort { error: Option<String> },
+  /// Send the request with `headers` merged over its own and an optional new body.
+  Continue {
+    #[serde(default)]
+    headers: HashMap<String, String>,
+    post_data: Option<String>,
+  },
+}
+
+fn default_route_status() -> i64 {
+  200
+}
+
+#[derive(Serialize)]
+pub struct RouteAddedDto {
+  pub route_id: String,
+}
+
+#[derive(Deserialize)]
+pub struct DeleteRoutesDto {
+  /// The route to remove; every route of the tab when omitted.
+  pub route_id: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct NetworkRulesDto {
+  pub block: BlockDto,
+  /// Requests blocked since the tab was opened.
+  pub blocked: u64,
+}
+
+/// Regional settings a tab pretends to have.
+#[derive(Deserialize, Default)]
+pub struct RegionDto {
+  /// ICU locale, e.g. `de-DE`.
+  pub locale: Option<String>,
+  /// IANA timezone, e.g. `Europe/Berlin`.
+  pub timezone_id: Option<String>,
+  /// Needs a browser context of the tab's own or of a session.
+  pub geolocation: Option<GeolocationDto>,
+  /// Accept-Language header value; derived from the profile languages or `locale` when omitted.
+  pub accept_language: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct GeolocationDto {
+  pub latitude: f64,
+  pub longitude: f64,
+  /// Accuracy in meters (default `100`).
+  pub accuracy: Option<f64>,
+}
+
+/// The point at which a navigation counts as finished.
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum WaitUntilDto {
+  /// The `load` event fired.
+  #[default]
+  Load,
+  /// The `DOMContentLoaded` event fired; images and stylesheets may still be loading.
+  DomContentLoaded,
+  /// The page loaded and had no network connections for at least 500 ms.
+  NetworkIdle,
+  /// The page loaded and an element matching the selector exists.
+  Selector(String),
+}
+
 impl OpenDto {
   #[must_use]
   pub(crate) fn bounded_expiration(&self) -> u64 {
     self.expiration.clamp(1, 3600)
   }
+
+  #[must_use]
+  pub(crate) fn bounded_idle_timeout(&self) -> Option<u64> {
+    self
+      .idle_timeout
+      .map(|idle_timeout| idle_timeout.clamp(1, 3600))
+  }
+}
+
+/// Expiration update for an open tab; both variants are clamped to 1..=3600 seconds.
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExpirationDto {
+  /// Pushes the current deadline forward by the given number of seconds.
+  Extend(u64),
+  /// Sets a new deadline the given number of seconds from now.
+  Reset(u64),
+}
+
+impl ExpirationDto {
+  #[must_use]
+  pub(crate) fn deadline(&self, current: SystemTime, now: SystemTime) -> SystemTime {
+    match self {
+      ExpirationDto::Extend(seconds) => current + Duration::from_secs((*seconds).clamp(1, 3600)),
+      ExpirationDto::Reset(seconds) => now + Duration::from_secs((*seconds).clamp(1, 3600)),
+    }
+  }
+}
+
+#[derive(Deserialize)]
+pub struct NavigateDto {
+  pub url: String,
+  #[serde(default)]
+  pub wait_until: WaitUntilDto,
+  #[serde(default = "default_timeout_ms")]
+  pub timeout_ms: u64,
+  pub referrer: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct CookieDto {
+  pub name: String,
+  pub value: String,
+  pub url: Option<String>,
+  pub domain: Option<String>,
+  pub path: Option<String>,
+  /// Expiration in seconds since the Unix epoch; a session cookie when omitted.
+  pub expires: Option<f64>,
+  pub http_only: Option<bool>,
+  pub secure: Option<bool>,
+  pub same_site: Option<CookieSameSite>,
+}
+
+#[derive(Deserialize)]
+pub struct SetCookiesDto {
+  pub cookies: Vec<CookieDto>,
+}
+
+#[derive(Deserialize)]
+pub struct DeleteCookiesDto {
+  pub name: Option<String>,
+  pub domain: Option<String>,
+  pub path: Option<String>,
+}
+
+/// Cookies and localStorage in the Playwright `storageState` JSON shape.
+#[derive(Serialize, Deserialize)]
+pub struct StorageStateDto {
+  #[serde(default)]
+  pub cookies: Vec<StorageCookieDto>,
+  #[serde(default)]
+  pub origins: Vec<OriginStateDto>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageCookieDto {
+  pub name: String,
+  pub value: String,
+  pub domain: String,
+  pub path: String,
+  /// Expiration in seconds since the Unix epoch; `-1` for session cookies.
+  pub expires: f64,
+  pub http_only: bool,
+  pub secure: bool,
+  pub same_site: CookieSameSite,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OriginStateDto {
+  pub origin: String,
+  pub local_storage: Vec<StorageItemDto>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct StorageItemDto {
+  pub name: String,
+  pub value: String,
 }
 
 #[derive(Deserialize)]
@@ -49,3 +453,423 @@ pub struct ExecuteDto {
   pub selector: Option<String>,
   pub function: String,
 }
+
+#[derive(Serialize)]
+pub struct TabDto {
+  pub tab_id: String,
+  pub url: Option<String>,
+  pub title: Option<String>,
+  pub created_at: u64,
+  pub expires_at: u64,
+  pub last_activity_at: u64,
+  pub idle_timeout: Option<u64>,
+  pub isolated: bool,
+  pub session_id: Option<String>,
+  pub crashed: bool,
+}
+
+#[derive(Deserialize)]
+pub struct WaitForResponseDto {
+  /// Glob matched against the whole response URL.
+  pub url: String,
+  /// HTTP method of the request; any method when omitted.
+  pub method: Option<String>,
+  #[serde(default = "default_timeout_ms")]
+  pub timeout_ms: u64,
+  /// Selector of an element to click once the wait is armed.
+  pub click: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct NetworkResponseDto {
+  pub url: String,
+  pub status: i64,
+  pub headers: HashMap<String, String>,
+  pub body: String,
+  /// `body` is binary data encoded as base64.
+  pub base64_encoded: bool,
+}
+
+/// Severity of a console message, from the least to the most severe.
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsoleLevelDto {
+  Debug,
+  Info,
+  Warning,
+  Error,
+}
+
+#[derive(Deserialize)]
+pub struct ConsoleQueryDto {
+  /// Only messages of at least this severity.
+  pub level: Option<ConsoleLevelDto>,
+  /// Only messages logged after this moment, in milliseconds since the Unix epoch.
+  pub since: Option<u64>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct ConsoleMessageDto {
+  pub level: ConsoleLevelDto,
+  /// `console-api` for `console.*` calls, otherwise the browser's log source
+  /// (e.g. `network`, `javascript`, `security`).
+  pub source: String,
+  pub text: String,
+  /// URL of the script or resource the message comes from, if known.
+  pub url: Option<String>,
+  /// Line in `url` as reported by the browser, if known.
+  pub line: Option<i64>,
+  /// Milliseconds since the Unix epoch.
+  pub timestamp: u64,
+}
+
+/// What happens when the page opens an `alert`, `confirm`, `prompt` or `beforeunload` dialog.
+#[derive(Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DialogActionDto {
+  /// Keep the dialog open until it is answered through the API; the page is blocked
+  /// meanwhile and page actions such as `click` and `execute` fail fast.
+  #[default]
+  Manual,
+  Accept,
+  Dismiss,
+}
+
+#[derive(Deserialize, Default)]
+pub struct DialogPolicyDto {
+  #[serde(default)]
+  pub action: DialogActionDto,
+  /// Text entered into `prompt` dialogs accepted by the policy; their default text when omitted.
+  pub prompt_text: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct AnswerDialogDto {
+  pub accept: bool,
+  /// Text entered into a `prompt` dialog when accepting it.
+  pub prompt_text: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct DialogDto {
+  pub dialog_id: String,
+  /// `alert`, `confirm`, `prompt` or `beforeunload`.
+  pub kind: String,
+  pub message: String,
+  /// URL of the frame that opened the dialog.
+  pub url: String,
+  pub default_prompt: Option<String>,
+  /// Milliseconds since the Unix epoch.
+  pub opened_at: u64,
+  /// How the dialog was closed; `None` while it is pending.
+  pub closed: Option<DialogClosedDto>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct DialogClosedDto {
+  pub accepted: bool,
+  /// Text entered into a `prompt` dialog.
+  pub user_input: String,
+  /// Milliseconds since the Unix epoch.
+  pub closed_at: u64,
+}
+
+#[derive(Serialize)]
+pub struct DialogsDto {
+  /// Dialogs waiting for an answer.
+  pub pending: Vec<DialogDto>,
+  /// Closed dialogs, oldest first.
+  pub history: Vec<DialogDto>,
+}
+
+#[derive(Serialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum PageErrorKindDto {
+  /// An uncaught JavaScript exception or unhandled promise rejection.
+  Exception,
+  /// The renderer of the tab crashed.
+  Crash,
+}
+
+#[derive(Serialize, Clone)]
+pub struct PageErrorDto {
+  pub kind: PageErrorKindDto,
+  pub message: String,
+  /// The JavaScript stack of an exception, if known.
+  pub stack: Option<String>,
+  /// URL of the script that threw, if known.
+  pub url: Option<String>,
+  /// Line in `url` as reported by the browser, if known.
+  pub line: Option<i64>,
+  pub column: Option<i64>,
+  /// Milliseconds since the Unix epoch.
+  pub timestamp: u64,
+}
+
+#[derive(Serialize)]
+pub struct PageErrorsDto {
+  /// The renderer crashed; the tab only accepts `errors` and `close` calls.
+  pub crashed: bool,
+  /// Uncaught exceptions are recorded (the tab was opened with `errors` or `console`);
+  /// otherwise `errors` only ever holds the crash.
+  pub exceptions: bool,
+  pub errors: Vec<PageErrorDto>,
+}
+
+#[derive(Deserialize, Default)]
+pub struct HarOptionsDto {
+  /// Include response bodies of up to 1 MiB each and 64 MiB per tab.
+  #[serde(default)]
+  pub content: bool,
+}
+
+/// A HAR 1.2 archive.
+#[derive(Serialize)]
+pub struct HarDto {
+  pub log: HarLogDto,
+}
+
+#[derive(Serialize)]
+pub struct HarLogDto {
+  pub version: &'static str,
+  pub creator: HarCreatorDto,
+  pub entries: Vec<HarEntryDto>,
+}
+
+#[derive(Serialize)]
+pub struct HarCreatorDto {
+  pub name: &'static str,
+  pub version: &'static str,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HarEntryDto {
+  pub started_date_time: String,
+  /// Total time of the request in milliseconds.
+  pub time: f64,
+  pub request: HarRequestDto,
+  pub response: HarResponseDto,
+  pub cache: HarCacheDto,
+  pub timings: HarTimingsDto,
+  #[serde(rename = "serverIPAddress", skip_serializing_if = "Option::is_none")]
+  pub server_ip_address: Option<String>,
+  #[serde(rename = "_resourceType", skip_serializing_if = "Option::is_none")]
+  pub resource_type: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HarRequestDto {
+  pub method: String,
+  pub url: String,
+  pub http_version: String,
+  pub cookies: Vec<HarNameValueDto>,
+  pub headers: Vec<HarNameValueDto>,
+  pub query_string: Vec<HarNameValueDto>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub post_data: Option<HarPostDataDto>,
+  pub headers_size: i64,
+  pub body_size: i64,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HarResponseDto {
+  pub status: i64,
+  pub status_text: String,
+  pub http_version: String,
+  pub cookies: Vec<HarNameValueDto>,
+  pub headers: Vec<HarNameValueDto>,
+  pub content: HarContentDto,
+  #[serde(rename = "redirectURL")]
+  pub redirect_url: String,
+  pub headers_size: i64,
+  pub body_size: i64,
+  /// Network error of a failed request.
+  #[serde(rename = "_error", skip_serializing_if = "Option::is_none")]
+  pub error: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HarContentDto {
+  pub size: i64,
+  pub mime_type: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub text: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub encoding: Option<String>,
+  /// The body was too large to keep and is left out of `text`.
+  #[serde(rename = "_truncated", skip_serializing_if = "std::ops::Not::not")]
+  pub truncated: bool,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HarPostDataDto {
+  pub mime_type: String,
+  pub text: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct HarNameValueDto {
+  pub name: String,
+  pub value: String,
+}
+
+#[derive(Serialize, Clone, Default)]
+pub struct HarCacheDto {}
+
+/// Phases of a request in milliseconds; `-1` when a phase does not apply.
+#[derive(Serialize, Clone)]
+pub struct HarTimingsDto {
+  pub blocked: f64,
+  pub dns: f64,
+  pub connect: f64,
+  pub ssl: f64,
+  pub send: f64,
+  pub wait: f64,
+  pub receive: f64,
+}
+
+#[derive(Serialize)]
+pub struct NavigationDto {
+  pub url: Option<String>,
+  pub title: Option<String>,
+  /// HTTP status of the main document; `None` when it was not loaded over the network.
+  pub status: Option<i64>,
+  /// Response headers of the main document.
+  pub headers: HashMap<String, String>,
+  /// Redirects followed before the main document, in order.
+  pub redirect_chain: Vec<RedirectDto>,
+}
+
+#[derive(Serialize)]
+pub struct RedirectDto {
+  pub url: String,
+  pub status: i64,
+}
+
+#[derive(Serialize)]
+pub struct TabOpenedDto {
+  pub tab_id: String,
+  /// Expiration deadline in milliseconds since the Unix epoch.
+  pub expires_at: u64,
+  #[serde(flatten)]
+  pub navigation: NavigationDto,
+}
+
+#[derive(Serialize)]
+pub struct TabExpirationDto {
+  pub tab_id: String,
+  pub expires_at: u64,
+}
+
+#[derive(Serialize)]
+pub struct ProxyStatsDto {
+  pub server: String,
+  pub healthy: bool,
+  /// Open tabs currently using the proxy.
+  pub in_use: usize,
+  pub uses: u64,
+  pub successes: u64,
+  pub failures: u64,
+  pub cooldown_until: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct ExpirationsDto {
+  pub pending: usize,
+  pub overdue: usize,
+  pub next_due_at: Option<u64>,
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn at(seconds: u64) -> SystemTime {
+    SystemTime::UNIX_EPOCH + Duration::from_secs(seconds)
+  }
+
+  #[test]
+  fn extend_pushes_the_current_deadline_forward() {
+    let deadline = ExpirationDto::Extend(60).deadline(at(1_000), at(500));
+
+    assert_eq!(deadline, at(1_060));
+  }
+
+  #[test]
+  fn reset_sets_the_deadline_relative_to_now() {
+    let deadline = ExpirationDto::Reset(60).deadline(at(1_000), at(500));
+
+    assert_eq!(deadline, at(560));
+  }
+
+  #[test]
+  fn expiration_updates_are_clamped_to_one_hour() {
+    assert_eq!(
+      ExpirationDto::Extend(10_000).deadline(at(1_000), at(500)),
+      at(4_600)
+    );
+    assert_eq!(
+      ExpirationDto::Reset(10_000).deadline(at(1_000), at(500)),
+      at(4_100)
+    );
+  }
+
+  #[test]
+  fn expiration_updates_last_at_least_one_second() {
+    assert_eq!(
+      ExpirationDto::Extend(0).deadline(at(1_000), at(500)),
+      at(1_001)
+    );
+    assert_eq!(
+      ExpirationDto::Reset(0).deadline(at(1_000), at(500)),
+      at(501)
+    );
+  }
+
+  #[test]
+  fn expiration_updates_deserialize_from_a_tagged_object() {
+    let extend = serde_json::from_str::<ExpirationDto>(r#"{"extend": 30}"#).expect("valid");
+    let reset = serde_json::from_str::<ExpirationDto>(r#"{"reset": 30}"#).expect("valid");
+
+    assert!(matches!(extend, ExpirationDto::Extend(30)));
+    assert!(matches!(reset, ExpirationDto::Reset(30)));
+  }
+
+  #[test]
+  fn mobile_devices_emulate_touch_at_their_pixel_ratio() {
+    for (device, width, height, scale) in [
+      (DeviceDto::Iphone, 390, 844, 3.0),
+      (DeviceDto::Pixel, 412, 915, 2.625),
+      (DeviceDto::Ipad, 820, 1180, 2.0),
+    ] {
+      let viewport = device.viewport();
+
+      assert_eq!((viewport.width, viewport.height), (width, height));
+      assert_eq!(viewport.device_scale_factor, Some(scale));
+      assert!(viewport.is_mobile);
+      assert!(viewport.has_touch);
+    }
+  }
+
+  #[test]
+  fn desktop_device_is_not_mobile() {
+    let viewport = DeviceDto::DesktopHd.viewport();
+
+    assert_eq!((viewport.width, viewport.height), (1920, 1080));
+    assert_eq!(viewport.device_scale_factor, Some(1.0));
+    assert!(!viewport.is_mobile);
+    assert!(!viewport.has_touch);
+  }
+
+  #[test]
+  fn devices_deserialize_from_snake_case_names() {
+    let device = serde_json::from_str::<DeviceDto>(r#""desktop_hd""#).expect("valid");
+
+    assert!(matches!(device, DeviceDto::DesktopHd));
+  }
+}
@@ -0,0 +1,168 @@
+use chaser_oxide::Page;
+use chaser_oxide::cdp::browser_protocol::inspector::{EnableParams, EventTargetCrashed};
+use chaser_oxide::cdp::js_protocol::runtime::{self, EventExceptionThrown};
+use futures::stream::{self, StreamExt};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::SystemTime;
+
+use crate::browser::tab::dto::{PageErrorDto, PageErrorKindDto, PageErrorsDto};
+use crate::browser::tab::models::{TabTask, to_unix_millis};
+use crate::models::{Error, ErrorInfo};
+
+/// Errors kept per tab; the oldest error is dropped for every new one beyond this.
+const MAX_ERRORS: usize = 100;
+
+#[derive(Default)]
+struct ErrorState {
+  errors: VecDeque<PageErrorDto>,
+  crashed: bool,
+}
+
+/// The error watch of a tab; stops when dropped together with the tab record.
+pub struct ErrorRecorder {
+  state: Arc<Mutex<ErrorState>>,
+  /// Uncaught exceptions are recorded, not only the crash.
+  exceptions: bool,
+  _task: TabTask,
+}
+
+impl ErrorRecorder {
+  /// Whether the renderer of the tab crashed.
+  #[must_use]
+  pub fn crashed(&self) -> bool {
+    self
+      .state
+      .lock()
+      .unwrap_or_else(PoisonError::into_inner)
+      .crashed
+  }
+
+  /// Returns the recorded errors, oldest first, with the crash state.
+  #[must_use]
+  pub fn export(&self) -> PageErrorsDto {
+    let state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+    PageErrorsDto {
+      crashed: state.crashed,
+      exceptions: self.exceptions,
+      errors: state.errors.iter().cloned().collect(),
+    }
+  }
+}
+
+/// Starts watching a page for uncaught exceptions and renderer crashes.
+///
+/// # Behavior
+///
+/// - Enables the `Inspector` domain and listens to `Inspector.targetCrashed`; a crash is
+///   recorded and marks the tab as crashed for good.
+/// - Listens to `Runtime.exceptionThrown` and enables the `Runtime` domain it comes from
+///   when `exceptions` is set; pages can detect the domain, so it is opt-in.
+/// - Keeps the latest 100 errors per tab in a ring buffer.
+///
+/// The watch runs as a background task that is aborted when the returned `ErrorRecorder`
+/// is dropped.
+///
+/// # Errors
+///
+/// Returns an `Error` if subscribing to the events or enabling the `Inspector` or `Runtime`
+/// domain fails.
+///
+/// # Examples
+///
+/// ```ignore
+/// let recorder = errors::watch(chaser.raw_page(), true).await?;
+/// ```
+pub async fn watch(page: &Page, exceptions: bool) -> Result<ErrorRecorder, Error> {
+  enum Event {
+    Exception(Arc<EventExceptionThrown>),
+    Crash,
+  }
+
+  fn watch_error(e: impl std::fmt::Display) -> Error {
+    Error::Operation(ErrorInfo {
+      message: format!("Failed to watch page errors: {e}"),
+      code: None,
+    })
+  }
+  fn from_exception(event: &EventExceptionThrown) -> PageErrorDto {
+    let details = &event.exception_details;
+    let description = details
+      .exception
+      .as_ref()
+      .and_then(|exception| exception.description.clone());
+    // The description of an Error holds its message followed by the stack.
+    let message = description
+      .as_deref()
+      .and_then(|description| description.lines().next())
+      .map_or_else(|| details.text.clone(), ToString::to_string);
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let timestamp = event.timestamp.inner().max(0.0) as u64;
+
+    PageErrorDto {
+      kind: PageErrorKindDto::Exception,
+      message,
+      stack: description.filter(|description| description.contains('\n')),
+      url: details.url.clone().filter(|url| !url.is_empty()),
+      line: Some(details.line_number),
+      column: Some(details.column_number),
+      timestamp,
+    }
+  }
+
+  let thrown = page
+    .event_listener::<EventExceptionThrown>()
+    .await
+    .map_err(watch_error)?;
+  let crashes = page
+    .event_listener::<EventTargetCrashed>()
+    .await
+    .map_err(watch_error)?;
+  page
+    .execute(EnableParams::default())
+    .await
+    .map_err(watch_error)?;
+  if exceptions {
+    page
+      .execute(runtime::EnableParams::default())
+      .await
+      .map_err(watch_error)?;
+  }
+
+  let state = Arc::new(Mutex::new(ErrorState::default()));
+  let recorded = state.clone();
+  let task = tokio::spawn(async move {
+    let mut events = stream::select(thrown.map(Event::Exception), crashes.map(|_| Event::Crash));
+
+    while let Some(event) = events.next().await {
+      let error = match event {
+        Event::Exception(event) => from_exception(&event),
+        Event::Crash => PageErrorDto {
+          kind: PageErrorKindDto::Crash,
+          message: "The renderer of the tab crashed".to_string(),
+          stack: None,
+          url: None,
+          line: None,
+          column: None,
+          timestamp: to_unix_millis(SystemTime::now()),
+        },
+      };
+
+      let mut state = recorded.lock().unwrap_or_else(PoisonError::into_inner);
+      if matches!(error.kind, PageErrorKindDto::Crash) {
+        tracing::warn!("A tab renderer crashed");
+        state.crashed = true;
+      }
+      if state.errors.len() >= MAX_ERRORS {
+        state.errors.pop_front();
+      }
+      state.errors.push_back(error);
+    }
+  });
+
+  Ok(ErrorRecorder {
+    state,
+    exceptions,
+    _task: TabTask::new(task.abort_handle()),
+  })
+}
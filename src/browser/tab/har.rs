@@ -0,0 +1,501 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use chaser_oxide::Page;
+use chaser_oxide::cdp::browser_protocol::network::{
+  EventLoadingFailed, EventLoadingFinished, EventRequestWillBeSent, EventResponseReceived,
+  GetResponseBodyParams, Headers, Request, Response,
+};
+use futures::stream::{self, FuturesUnordered, StreamExt};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+use url::Url;
+
+use crate::browser::tab::dto::{
+  HarCacheDto, HarContentDto, HarCreatorDto, HarDto, HarEntryDto, HarLogDto, HarNameValueDto,
+  HarOptionsDto, HarPostDataDto, HarRequestDto, HarResponseDto, HarTimingsDto,
+};
+use crate::browser::tab::models::TabTask;
+use crate::models::{Error, ErrorInfo};
+
+/// Requests recorded per tab; later requests are not recorded.
+const MAX_ENTRIES: usize = 10_000;
+
+/// Response body bytes kept per request; larger bodies are left out and marked truncated.
+const MAX_BODY_SIZE: i64 = 1 << 20;
+
+/// Response body bytes kept per tab; bodies beyond this budget are left out and marked
+/// truncated.
+const MAX_TAB_BODY_SIZE: i64 = 64 << 20;
+
+/// A recorded request with the monotonic timestamps (in seconds) needed for its timings.
+struct Recording {
+  entry: HarEntryDto,
+  started: f64,
+  headers_received: Option<f64>,
+}
+
+#[derive(Default)]
+struct HarState {
+  recordings: Vec<Recording>,
+  by_request: HashMap<String, usize>,
+  /// Bytes of the response bodies kept so far.
+  body_bytes: i64,
+}
+
+impl HarState {
+  fn recording(&mut self, request_id: &str) -> Option<&mut Recording> {
+    self
+      .by_request
+      .get(request_id)
+      .copied()
+      .and_then(|index| self.recordings.get_mut(index))
+  }
+}
+
+/// The HAR recording of a tab; stops when dropped together with the tab record.
+pub struct HarRecorder {
+  state: Arc<Mutex<HarState>>,
+  _task: TabTask,
+}
+
+impl HarRecorder {
+  /// Returns the traffic recorded so far as a HAR 1.2 archive.
+  #[must_use]
+  pub fn export(&self) -> HarDto {
+    let entries = self
+      .state
+      .lock()
+      .unwrap_or_else(PoisonError::into_inner)
+      .recordings
+      .iter()
+      .map(|recording| recording.entry.clone())
+      .collect();
+
+    HarDto {
+      log: HarLogDto {
+        version: "1.2",
+        creator: HarCreatorDto {
+          name: env!("CARGO_PKG_NAME"),
+          version: env!("CARGO_PKG_VERSION"),
+        },
+        entries,
+      },
+    }
+  }
+}
+
+/// Starts recording the network traffic of a page.
+///
+/// # Behavior
+///
+/// - Listens to the `Network` domain events of the page: requests, redirects, responses,
+///   finished and failed loads.
+/// - Reads response bodies via `Network.getResponseBody` when `options.content` is set,
+///   concurrently with the events; binary bodies are kept base64-encoded. Bodies over
+///   1 MiB, and bodies beyond 64 MiB kept per tab, are left out and marked with
+///   `_truncated`. The limits apply to the decoded body once it is read; only bodies
+///   whose `Content-Length` already exceeds them are not read at all.
+/// - Records at most 10 000 requests per tab.
+///
+/// The recording runs as a background task that is aborted when the returned
+/// `HarRecorder` is dropped.
+///
+/// # Errors
+///
+/// Returns an `Error` if subscribing to the `Network` events fails.
+///
+/// # Examples
+///
+/// ```ignore
+/// let recorder = har::record(chaser.raw_page(), HarOptionsDto { content: true }).await?;
+/// ```
+pub async fn record(page: &Page, options: HarOptionsDto) -> Result<HarRecorder, Error> {
+  enum Event {
+    Request(Arc<EventRequestWillBeSent>),
+    Response(Arc<EventResponseReceived>),
+    Finished(Arc<EventLoadingFinished>),
+    Failed(Arc<EventLoadingFailed>),
+  }
+
+  fn recording_error(e: impl std::fmt::Display) -> Error {
+    Error::Operation(ErrorInfo {
+      message: format!("Failed to record network traffic: {e}"),
+      code: None,
+    })
+  }
+  fn on_request(state: &mut HarState, event: &EventRequestWillBeSent) {
+    let request_id = event.request_id.inner().clone();
+    let redirected = event
+      .redirect_response
+      .as_ref()
+      .zip(state.recording(request_id.as_str()));
+    if let Some((response, recording)) = redirected {
+      recording.entry.response = to_har_response(response, event.request.url.clone());
+      recording.entry.time = (event.timestamp.inner() - recording.started) * 1000.0;
+    }
+
+    if state.recordings.len() >= MAX_ENTRIES {
+      state.by_request.remove(&request_id);
+      return;
+    }
+
+    state.by_request.insert(request_id, state.recordings.len());
+    state.recordings.push(Recording {
+      entry: HarEntryDto {
+        started_date_time: format_wall_time(*event.wall_time.inner()),
+        time: 0.0,
+        request: to_har_request(&event.request),
+        response: HarResponseDto {
+          status: 0,
+          status_text: String::new(),
+          http_version: String::new(),
+          cookies: Vec::new(),
+          headers: Vec::new(),
+          content: HarContentDto {
+            size: -1,
+            mime_type: String::new(),
+            text: None,
+            encoding: None,
+            truncated: false,
+          },
+          redirect_url: String::new(),
+          headers_size: -1,
+          body_size: -1,
+          error: None,
+        },
+        cache: HarCacheDto::default(),
+        timings: HarTimingsDto {
+          blocked: -1.0,
+          dns: -1.0,
+          connect: -1.0,
+          ssl: -1.0,
+          send: 0.0,
+          wait: 0.0,
+          receive: 0.0,
+        },
+        server_ip_address: None,
+        resource_type: event
+          .r#type
+          .as_ref()
+          .map(|kind| kind.as_ref().to_lowercase()),
+      },
+      started: *event.timestamp.inner(),
+      headers_received: None,
+    });
+  }
+  fn on_response(state: &mut HarState, event: &EventResponseReceived) {
+    let Some(recording) = state.recording(event.request_id.inner()) else {
+      return;
+    };
+
+    let response = &event.response;
+    recording.entry.response = to_har_response(response, String::new());
+    recording.entry.request.http_version = recording.entry.response.http_version.clone();
+    recording.entry.server_ip_address = response.remote_ip_address.clone();
+
+    match &response.timing {
+      Some(timing) => {
+        let phase = |start: f64, end: f64| if start < 0.0 { -1.0 } else { end - start };
+        recording.entry.timings = HarTimingsDto {
+          blocked: [timing.dns_start, timing.connect_start, timing.send_start]
+            .into_iter()
+            .find(|start| *start >= 0.0)
+            .unwrap_or(-1.0),
+          dns: phase(timing.dns_start, timing.dns_end),
+          connect: phase(timing.connect_start, timing.connect_end),
+          ssl: phase(timing.ssl_start, timing.ssl_end),
+          send: (timing.send_end - timing.send_start).max(0.0),
+          wait: (timing.receive_headers_end - timing.send_end).max(0.0),
+          receive: 0.0,
+        };
+        recording.headers_received =
+          Some(timing.request_time + timing.receive_headers_end / 1000.0);
+      }
+      None => {
+        recording.entry.timings.wait = (event.timestamp.inner() - recording.started) * 1000.0;
+        recording.headers_received = Some(*event.timestamp.inner());
+      }
+    }
+  }
+  /// Records the end of a request; returns whether its body is to be read.
+  fn on_finished(state: &mut HarState, event: &EventLoadingFinished, content: bool) -> bool {
+    let remaining = MAX_TAB_BODY_SIZE - state.body_bytes;
+    let Some(recording) = state.recording(event.request_id.inner()) else {
+      return false;
+    };
+
+    let finished = *event.timestamp.inner();
+    recording.entry.time = (finished - recording.started) * 1000.0;
+    recording.entry.timings.receive = recording
+      .headers_received
+      .map_or(0.0, |received| ((finished - received) * 1000.0).max(0.0));
+    #[allow(clippy::cast_possible_truncation)]
+    let size = event.encoded_data_length as i64;
+    recording.entry.response.body_size = size;
+    recording.entry.response.content.size = size;
+    if !content {
+      return false;
+    }
+
+    // Content-Length is at most the decoded size, so a body announced larger than what
+    // can be kept is not read at all; the decoded size is checked once the body is read.
+    let announced = header_values(&recording.entry.response.headers, "content-length")
+      .next()
+      .and_then(|length| length.trim().parse::<i64>().ok())
+      .unwrap_or(0);
+    let oversized = announced > MAX_BODY_SIZE.min(remaining) || remaining <= 0;
+    recording.entry.response.content.truncated = oversized;
+    !oversized
+  }
+  fn on_failed(state: &mut HarState, event: &EventLoadingFailed) {
+    if let Some(recording) = state.recording(event.request_id.inner()) {
+      recording.entry.time = (event.timestamp.inner() - recording.started) * 1000.0;
+      recording.entry.response.error = Some(event.error_text.clone());
+    }
+  }
+  async fn read_body(page: &Page, state: &Mutex<HarState>, event: Arc<EventLoadingFinished>) {
+    let Ok(body) = page
+      .execute(GetResponseBodyParams::new(event.request_id.clone()))
+      .await
+      .map(|response| response.result)
+    else {
+      return;
+    };
+
+    let mut state = state.lock().unwrap_or_else(PoisonError::into_inner);
+    let remaining = MAX_TAB_BODY_SIZE - state.body_bytes;
+    let Some(recording) = state.recording(event.request_id.inner()) else {
+      return;
+    };
+
+    let content = &mut recording.entry.response.content;
+    #[allow(clippy::cast_possible_wrap)]
+    let (size, kept) = {
+      let kept = body.body.len() as i64;
+      let size = if body.base64_encoded {
+        STANDARD
+          .decode(body.body.as_str())
+          .map_or(-1, |bytes| bytes.len() as i64)
+      } else {
+        kept
+      };
+      (size, kept)
+    };
+    content.size = size;
+    if size > MAX_BODY_SIZE || kept > remaining {
+      content.truncated = true;
+      return;
+    }
+    content.encoding = body.base64_encoded.then(|| "base64".to_string());
+    content.text = Some(body.body);
+    state.body_bytes += kept;
+  }
+
+  let requests = page
+    .event_listener::<EventRequestWillBeSent>()
+    .await
+    .map_err(recording_error)?;
+  let responses = page
+    .event_listener::<EventResponseReceived>()
+    .await
+    .map_err(recording_error)?;
+  let finished = page
+    .event_listener::<EventLoadingFinished>()
+    .await
+    .map_err(recording_error)?;
+  let failed = page
+    .event_listener::<EventLoadingFailed>()
+    .await
+    .map_err(recording_error)?;
+
+  let state = Arc::new(Mutex::new(HarState::default()));
+  let recorded = state.clone();
+  let page = page.clone();
+  let task = tokio::spawn(async move {
+    let mut events = stream::select(
+      stream::select(requests.map(Event::Request), responses.map(Event::Response)),
+      stream::select(finished.map(Event::Finished), failed.map(Event::Failed)),
+    );
+    // Bodies are read alongside the events, so a slow read does not hold up the others.
+    let mut reads = FuturesUnordered::new();
+
+    loop {
+      let event = tokio::select! {
+        event = events.next() => event,
+        Some(()) = reads.next(), if !reads.is_empty() => continue,
+      };
+      let Some(event) = event else {
+        break;
+      };
+
+      let finished = {
+        let mut state = recorded.lock().unwrap_or_else(PoisonError::into_inner);
+        match event {
+          Event::Request(event) => {
+            on_request(&mut state, &event);
+            None
+          }
+          Event::Response(event) => {
+            on_response(&mut state, &event);
+            None
+          }
+          Event::Failed(event) => {
+            on_failed(&mut state, &event);
+            None
+          }
+          Event::Finished(event) => {
+            on_finished(&mut state, &event, options.content).then_some(event)
+          }
+        }
+      };
+
+      if let Some(event) = finished {
+        reads.push(read_body(&page, &recorded, event));
+      }
+    }
+  });
+
+  Ok(HarRecorder {
+    state,
+    _task: TabTask::new(task.abort_handle()),
+  })
+}
+
+fn to_name_values(headers: &Headers) -> Vec<HarNameValueDto> {
+  headers
+    .inner()
+    .as_object()
+    .into_iter()
+    .flatten()
+    .flat_map(|(name, value)| {
+      // Chrome joins repeated headers (e.g. Set-Cookie) with newlines.
+      value
+        .as_str()
+        .unwrap_or_default()
+        .split('\n')
+        .map(|value| HarNameValueDto {
+          name: name.clone(),
+          value: value.to_string(),
+        })
+        .collect::<Vec<_>>()
+    })
+    .collect()
+}
+
+fn header_values<'a>(
+  headers: &'a [HarNameValueDto],
+  name: &'a str,
+) -> impl Iterator<Item = &'a str> + 'a {
+  headers
+    .iter()
+    .filter(move |header| header.name.eq_ignore_ascii_case(name))
+    .map(|header| header.value.as_str())
+}
+
+fn to_cookie(pair: &str) -> Option<HarNameValueDto> {
+  pair.split_once('=').map(|(name, value)| HarNameValueDto {
+    name: name.trim().to_string(),
+    value: value.trim().to_string(),
+  })
+}
+
+fn to_http_version(protocol: Option<&str>) -> String {
+  match protocol {
+    Some("h2") => "HTTP/2".to_string(),
+    Some("h3") => "HTTP/3".to_string(),
+    Some(protocol) => protocol.to_uppercase(),
+    None => String::new(),
+  }
+}
+
+fn to_har_request(request: &Request) -> HarRequestDto {
+  let headers = to_name_values(&request.headers);
+  let url = match &request.url_fragment {
+    Some(fragment) => format!("{}{fragment}", request.url),
+    None => request.url.clone(),
+  };
+  let query_string = Url::parse(request.url.as_str())
+    .map(|url| {
+      url
+        .query_pairs()
+        .map(|(name, value)| HarNameValueDto {
+          name: name.into_owned(),
+          value: value.into_owned(),
+        })
+        .collect()
+    })
+    .unwrap_or_default();
+  let cookies = header_values(&headers, "cookie")
+    .flat_map(|cookies| cookies.split(';'))
+    .filter_map(to_cookie)
+    .collect();
+  let post_data = request.post_data_entries.as_ref().map(|entries| {
+    let bytes = entries
+      .iter()
+      .filter_map(|entry| entry.bytes.as_ref())
+      .filter_map(|bytes| STANDARD.decode(AsRef::<str>::as_ref(bytes)).ok())
+      .flatten()
+      .collect::<Vec<_>>();
+    HarPostDataDto {
+      mime_type: header_values(&headers, "content-type")
+        .next()
+        .unwrap_or_default()
+        .to_string(),
+      text: String::from_utf8_lossy(&bytes).into_owned(),
+    }
+  });
+  #[allow(clippy::cast_possible_wrap)]
+  let body_size = post_data
+    .as_ref()
+    .map_or(0, |post_data| post_data.text.len() as i64);
+
+  HarRequestDto {
+    method: request.method.clone(),
+    url,
+    http_version: String::new(),
+    cookies,
+    headers,
+    query_string,
+    post_data,
+    headers_size: -1,
+    body_size,
+  }
+}
+
+fn to_har_response(response: &Response, redirect_url: String) -> HarResponseDto {
+  let headers = to_name_values(&response.headers);
+  let cookies = header_values(&headers, "set-cookie")
+    .filter_map(|cookie| cookie.split(';').next())
+    .filter_map(to_cookie)
+    .collect();
+
+  HarResponseDto {
+    status: response.status,
+    status_text: response.status_text.clone(),
+    http_version: to_http_version(response.protocol.as_deref()),
+    cookies,
+    headers,
+    content: HarContentDto {
+      size: -1,
+      mime_type: response.mime_type.clone(),
+      text: None,
+      encoding: None,
+      truncated: false,
+    },
+    redirect_url,
+    headers_size: -1,
+    body_size: -1,
+    error: None,
+  }
+}
+
+fn format_wall_time(seconds: f64) -> String {
+  #[allow(clippy::cast_possible_truncation)]
+  let nanos = (seconds * 1e9) as i128;
+  OffsetDateTime::from_unix_timestamp_nanos(nanos)
+    .ok()
+    .and_then(|time| time.format(&Rfc3339).ok())
+    .unwrap_or_default()
+}
@@ -1,2 +1,11 @@
 pub mod api;
+pub mod console;
+pub mod dialogs;
 pub mod dto;
+pub mod errors;
+pub mod har;
+pub mod models;
+pub mod network;
+pub mod proxies;
+pub mod reaper;
+pub mod slots;
@@ -0,0 +1,320 @@
+use chaser_oxide::auth::Credentials;
+use chaser_oxide::cdp::browser_protocol::browser::BrowserContextId;
+use chaser_oxide::{Browser, ChaserPage};
+use std::collections::HashMap;
+use std::env;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::OwnedSemaphorePermit;
+use tokio::task::AbortHandle;
+use url::Url;
+
+use serde::Deserialize;
+
+use crate::browser::tab::console::ConsoleRecorder;
+use crate::browser::tab::dialogs::DialogHandler;
+use crate::browser::tab::dto::{
+  DialogPolicyDto, HarOptionsDto, OsDto, PoolStrategyDto, ProfileDto, ProxyDto, ProxyPoolDto,
+  RegionDto, StorageStateDto, ViewportDto,
+};
+use crate::browser::tab::errors::ErrorRecorder;
+use crate::browser::tab::har::HarRecorder;
+use crate::browser::tab::network::{BlockRules, Interceptor};
+use crate::browser::tab::proxies::ProxyLease;
+
+/// The kind of browser context requested for a new tab.
+pub enum ContextKind {
+  Shared,
+  /// A context of its own, egressing through `proxy` or a proxy drawn from the pool.
+  Isolated {
+    proxy: Option<ProxyDto>,
+    pool: Option<ProxyPoolDto>,
+  },
+  Session(String),
+}
+
+/// Features of a new tab set up before its first navigation.
+pub struct TabFeatures {
+  pub http_credentials: Option<Credentials>,
+  pub block: Option<BlockRules>,
+  pub headers: HashMap<String, String>,
+  pub har: Option<HarOptionsDto>,
+  pub console: bool,
+  /// Records uncaught exceptions besides renderer crashes.
+  pub exceptions: bool,
+  pub dialog: DialogPolicyDto,
+}
+
+/// Emulation overrides of a new tab applied before its first navigation.
+pub struct TabEmulation {
+  pub profile: Option<ProfileDto>,
+  pub region: RegionDto,
+  pub viewport: Option<ViewportDto>,
+  pub storage_state: Option<StorageStateDto>,
+}
+
+/// A navigation of a tab's main frame.
+pub enum Navigation {
+  Goto { url: Url, referrer: Option<String> },
+  Back,
+  Forward,
+  Reload,
+}
+
+/// The browser context a tab lives in.
+pub enum TabContext {
+  /// The default browser profile shared by all non-isolated tabs.
+  Shared,
+  /// An incognito browser context owned by the tab and disposed together with it.
+  Isolated {
+    id: BrowserContextId,
+    browser: Arc<Browser>,
+  },
+  /// The browser context of a session, shared with the other tabs of the session
+  /// and disposed together with the session.
+  Session {
+    session_id: String,
+    id: BrowserContextId,
+  },
+}
+
+impl TabContext {
+  #[must_use]
+  pub fn id(&self) -> Option<BrowserContextId> {
+    match self {
+      TabContext::Shared => None,
+      TabContext::Isolated { id, .. } | TabContext::Session { id, .. } => Some(id.clone()),
+    }
+  }
+}
+
+/// A background task bound to a tab; aborted when dropped together with the tab record.
+pub struct TabTask(AbortHandle);
+
+impl TabTask {
+  #[must_use]
+  pub fn new(handle: AbortHandle) -> Self {
+    Self(handle)
+  }
+}
+
+impl Drop for TabTask {
+  fn drop(&mut self) {
+    self.0.abort();
+  }
+}
+
+/// An open tab tracked by the in-memory registry.
+pub struct Tab {
+  pub page: Arc<ChaserPage>,
+  pub context: TabContext,
+  pub created_at: SystemTime,
+  pub expires_at: SystemTime,
+  pub last_activity: SystemTime,
+  /// Closes the tab after this long without any API call touching it.
+  pub idle_timeout: Option<Duration>,
+  /// Page actions currently running on the tab; the tab is not idle while any runs.
+  pub in_flight: usize,
+  /// Slot taken from the `MAX_TABS` limit; released when the record is dropped.
+  pub slot: Option<OwnedSemaphorePermit>,
+  /// Request interception of the page (authentication, blocking, routes), if enabled.
+  pub interceptor: Option<Interceptor>,
+  /// Network traffic recording of the page, if enabled.
+  pub har: Option<HarRecorder>,
+  /// Console message capture of the page, if enabled.
+  pub console: Option<ConsoleRecorder>,
+  /// Uncaught exceptions and renderer crashes of the page.
+  pub errors: Option<ErrorRecorder>,
+  /// JavaScript dialog handling of the page.
+  pub dialogs: Option<DialogHandler>,
+  /// The pooled proxy the tab egresses through, if drawn from the pool.
+  pub proxy_lease: Option<ProxyLease>,
+}
+
+impl Tab {
+  /// Creates a tab record for a freshly created page. The tab expires immediately until
+  /// `with_expiration` is applied.
+  #[must_use]
+  pub fn new(
+    page: Arc<ChaserPage>,
+    context: TabContext,
+    slot: Option<OwnedSemaphorePermit>,
+  ) -> Self {
+    let now = SystemTime::now();
+    Self {
+      page,
+      context,
+      created_at: now,
+      expires_at: now,
+      last_activity: now,
+      idle_timeout: None,
+      in_flight: 0,
+      slot,
+      interceptor: None,
+      har: None,
+      console: None,
+      errors: None,
+      dialogs: None,
+      proxy_lease: None,
+    }
+  }
+
+  /// Makes the tab expire `expiration` seconds from now, or earlier after `idle_timeout`
+  /// seconds without activity.
+  #[must_use]
+  pub fn with_expiration(self, expiration: u64, idle_timeout: Option<u64>) -> Self {
+    let now = SystemTime::now();
+    Self {
+      expires_at: now + Duration::from_secs(expiration),
+      last_activity: now,
+      idle_timeout: idle_timeout.map(Duration::from_secs),
+      ..self
+    }
+  }
+
+  /// Whether the renderer of the tab crashed; a crashed tab can only be closed.
+  #[must_use]
+  pub fn is_crashed(&self) -> bool {
+    self.errors.as_ref().is_some_and(ErrorRecorder::crashed)
+  }
+
+  /// The moment the tab is due to be closed: its expiration deadline or the end of its
+  /// idle period, whichever comes first. The idle period does not run out while a page
+  /// action is in flight.
+  #[must_use]
+  pub fn due_at(&self) -> SystemTime {
+    match self.idle_timeout {
+      Some(idle) if self.in_flight == 0 => self.expires_at.min(self.last_activity + idle),
+      _ => self.expires_at,
+    }
+  }
+}
+
+/// What `open` does when all `MAX_TABS` slots are taken.
+pub enum LimitPolicy {
+  /// Fail immediately with HTTP 429.
+  Reject,
+  /// Wait in a FIFO queue of at most `size` requests for up to `timeout`, then fail with HTTP 429.
+  Queue { size: usize, timeout: Duration },
+}
+
+pub struct TabLimits {
+  pub max_tabs: Option<usize>,
+  pub policy: LimitPolicy,
+}
+
+impl TabLimits {
+  /// Create `TabLimits` by reading environment variables.
+  ///
+  /// Environment variables used:
+  /// - `MAX_TABS` (optional): maximum number of concurrently open tabs. Unlimited when unset.
+  /// - `MAX_TABS_POLICY` (optional): `reject` (default) or `queue`.
+  /// - `MAX_TABS_QUEUE_SIZE` (optional): maximum number of waiting requests (default `100`).
+  /// - `MAX_TABS_QUEUE_TIMEOUT` (optional): seconds a request may wait for a slot (default `30`).
+  ///
+  /// Unparsable values fall back to their defaults.
+  #[must_use]
+  pub fn from_env() -> Self {
+    fn parse_var<T: std::str::FromStr>(name: &str) -> Option<T> {
+      env::var(name)
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+    }
+
+    let policy = match env::var("MAX_TABS_POLICY").as_deref() {
+      Ok("queue") => LimitPolicy::Queue {
+        size: parse_var("MAX_TABS_QUEUE_SIZE").unwrap_or(100),
+        timeout: Duration::from_secs(parse_var("MAX_TABS_QUEUE_TIMEOUT").unwrap_or(30)),
+      },
+      _ => LimitPolicy::Reject,
+    };
+
+    Self {
+      max_tabs: parse_var::<usize>("MAX_TABS").filter(|max| *max > 0),
+      policy,
+    }
+  }
+}
+
+/// Stealth profile defaults of the server.
+pub struct StealthDefaults {
+  pub os: OsDto,
+}
+
+impl StealthDefaults {
+  /// Create `StealthDefaults` by reading environment variables.
+  ///
+  /// Environment variables used:
+  /// - `STEALTH_PROFILE` (optional): `windows` (default), `macos` or `linux`.
+  ///
+  /// Unknown values fall back to the default.
+  #[must_use]
+  pub fn from_env() -> Self {
+    let os = match env::var("STEALTH_PROFILE").as_deref().map(str::trim) {
+      Ok("macos") => OsDto::Macos,
+      Ok("linux") => OsDto::Linux,
+      _ => OsDto::Windows,
+    };
+
+    Self { os }
+  }
+}
+
+/// Proxy pool of the server, loaded from a JSON file.
+#[derive(Deserialize, Default)]
+pub struct ProxyPoolConfig {
+  #[serde(default)]
+  pub strategy: PoolStrategyDto,
+  /// Consecutive navigation failures after which a proxy cools down.
+  #[serde(default = "default_max_failures")]
+  pub max_failures: u32,
+  /// Cooldown of an unhealthy proxy in seconds.
+  #[serde(default = "default_cooldown")]
+  pub cooldown: u64,
+  pub proxies: Vec<ProxyDto>,
+}
+
+fn default_max_failures() -> u32 {
+  3
+}
+
+fn default_cooldown() -> u64 {
+  60
+}
+
+impl ProxyPoolConfig {
+  /// Create `ProxyPoolConfig` by reading environment variables.
+  ///
+  /// Environment variables used:
+  /// - `PROXY_POOL_FILE` (optional): path to a JSON file with `proxies` (each with
+  ///   `server` and optional `username`, `password` and `bypass`) and optional `strategy`
+  ///   (`round_robin` by default, `least_used` or `sticky`), `max_failures` (default `3`)
+  ///   and `cooldown` in seconds (default `60`).
+  ///
+  /// A missing variable leaves the pool empty; an unreadable or invalid file is logged and
+  /// leaves the pool empty as well.
+  #[must_use]
+  pub fn from_env() -> Self {
+    let Ok(path) = env::var("PROXY_POOL_FILE") else {
+      return Self::default();
+    };
+
+    std::fs::read_to_string(&path)
+      .map_err(|e| e.to_string())
+      .and_then(|content| serde_json::from_str::<Self>(&content).map_err(|e| e.to_string()))
+      .inspect(|config| tracing::info!("Loaded {} proxies from {path}", config.proxies.len()))
+      .unwrap_or_else(|e| {
+        tracing::error!("Failed to load the proxy pool from {path}: {e}");
+        Self::default()
+      })
+  }
+}
+
+/// Converts a point in time to milliseconds since the Unix epoch.
+#[must_use]
+pub fn to_unix_millis(time: SystemTime) -> u64 {
+  time
+    .duration_since(UNIX_EPOCH)
+    .map(|d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX))
+    .unwrap_or_default()
+}
@@ -0,0 +1,935 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use chaser_oxide::auth::Credentials;
+use chaser_oxide::cdp::browser_protocol::fetch::{
+  AuthChallengeResponse, AuthChallengeResponseResponse, AuthChallengeSource, ContinueRequestParams,
+  ContinueWithAuthParams, EnableParams, EventAuthRequired, EventRequestPaused, FailRequestParams,
+  FulfillRequestParams, HeaderEntry, RequestPattern,
+};
+use chaser_oxide::cdp::browser_protocol::network::{
+  ErrorReason, EventLoadingFailed, EventLoadingFinished, EventRequestWillBeSent,
+  EventResponseReceived, GetResponseBodyParams, Headers, RequestId, ResourceType, Response,
+};
+use chaser_oxide::cdp::browser_protocol::target::CreateBrowserContextParams;
+use chaser_oxide::error::CdpError;
+use chaser_oxide::listeners::EventStream;
+use chaser_oxide::{Binary, Page};
+use futures::stream::{self, StreamExt};
+use regex::Regex;
+use std::collections::{HashMap, VecDeque};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, PoisonError, RwLock};
+use url::Url;
+use uuid::Uuid;
+
+use crate::browser::tab::dto::{
+  BlockDto, NetworkResponseDto, NetworkRulesDto, ProxyDto, RouteActionDto, RouteDto,
+};
+use crate::browser::tab::models::TabTask;
+use crate::models::{Error, ErrorInfo};
+
+/// Maximum number of answered auth challenges remembered to detect repeated ones.
+const MAX_ATTEMPTED_CHALLENGES: usize = 1_000;
+
+/// Builds the parameters of a browser context that egresses through the given proxy.
+///
+/// # Behavior
+///
+/// - Accepts `http`, `https`, `socks4` and `socks5` proxy URLs.
+/// - Passes `proxy.bypass` through as the proxy bypass list.
+/// - Returns the default parameters when no proxy is given.
+///
+/// # Errors
+///
+/// Returns an `Error` if:
+/// - The proxy URL is invalid or uses an unsupported scheme.
+/// - Credentials are given for a SOCKS proxy, which Chrome cannot authenticate against.
+///
+/// # Examples
+///
+/// ```ignore
+/// let params = network::context_params(dto.proxy.as_ref())?;
+/// ```
+pub fn context_params(proxy: Option<&ProxyDto>) -> Result<CreateBrowserContextParams, Error> {
+  let Some(proxy) = proxy else {
+    return Ok(CreateBrowserContextParams::default());
+  };
+
+  let server = Url::parse(proxy.server.as_str()).map_err(|e| {
+    Error::Operation(ErrorInfo {
+      message: format!("Invalid proxy server '{}': {e}", proxy.server),
+      code: None,
+    })
+  })?;
+
+  match server.scheme() {
+    "http" | "https" => Ok(()),
+    "socks4" | "socks5" if proxy.username.is_none() => Ok(()),
+    "socks4" | "socks5" => Err(Error::NotSupported(
+      "authentication is not supported for SOCKS proxies".to_string(),
+    )),
+    scheme => Err(Error::NotSupported(format!("proxy scheme {scheme}"))),
+  }?;
+
+  Ok(CreateBrowserContextParams {
+    proxy_server: Some(proxy.server.clone()),
+    proxy_bypass_list: proxy.bypass.clone(),
+    ..CreateBrowserContextParams::default()
+  })
+}
+
+/// Compiled request blocking rules of a tab.
+#[derive(Default)]
+pub struct BlockRules {
+  dto: BlockDto,
+  resource_types: Vec<ResourceType>,
+  urls: Vec<Regex>,
+}
+
+impl BlockRules {
+  /// Validates and compiles blocking rules.
+  ///
+  /// # Behavior
+  ///
+  /// - Resource types are CDP resource types, case-insensitive (e.g. `image`, `font`).
+  /// - `url_patterns` are globs where `*` matches any characters and `?` exactly one,
+  ///   matched against the whole URL.
+  /// - `url_regexes` are regular expressions matched anywhere in the URL.
+  ///
+  /// # Errors
+  ///
+  /// Returns an `Error` if a resource type is unknown or a regular expression is invalid.
+  pub fn compile(dto: BlockDto) -> Result<Self, Error> {
+    let resource_types = dto
+      .resource_types
+      .iter()
+      .map(|name| {
+        parse_cdp_name::<ResourceType>(name)
+          .ok_or_else(|| rules_error(format!("Unknown resource type '{name}'")))
+      })
+      .collect::<Result<Vec<_>, _>>()?;
+
+    let urls = dto
+      .url_patterns
+      .iter()
+      .map(|glob| glob_to_regex(glob.as_str()))
+      .chain(dto.url_regexes.iter().cloned())
+      .map(|pattern| {
+        Regex::new(pattern.as_str())
+          .map_err(|e| rules_error(format!("Invalid URL pattern '{pattern}': {e}")))
+      })
+      .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Self {
+      dto,
+      resource_types,
+      urls,
+    })
+  }
+
+  fn blocks(&self, event: &EventRequestPaused) -> bool {
+    self.resource_types.contains(&event.resource_type)
+      || self
+        .urls
+        .iter()
+        .any(|url| url.is_match(event.request.url.as_str()))
+  }
+}
+
+/// What a route does with the requests it matches.
+#[derive(Clone)]
+enum RouteAction {
+  Fulfill {
+    status: i64,
+    headers: Vec<HeaderEntry>,
+    body: Binary,
+  },
+  Abort(ErrorReason),
+  Continue {
+    headers: HashMap<String, String>,
+    post_data: Option<Binary>,
+  },
+}
+
+/// A compiled route rule of a tab.
+pub struct Route {
+  id: String,
+  url: Regex,
+  method: Option<String>,
+  action: RouteAction,
+}
+
+impl Route {
+  /// Validates and compiles a route rule.
+  ///
+  /// # Behavior
+  ///
+  /// - `url` is a glob matched against the whole URL, as in the blocking rules.
+  /// - `method` is compared case-insensitively; any method matches when omitted.
+  /// - Fulfill bodies are sent as text unless `body_base64` is set.
+  /// - Abort errors are CDP network error reasons (`Failed` by default).
+  ///
+  /// # Errors
+  ///
+  /// Returns an `Error` if the fulfill status is outside 100..=599, the abort error reason
+  /// is unknown or a base64 body is invalid.
+  pub fn compile(dto: RouteDto) -> Result<Self, Error> {
+    let action = match dto.action {
+      RouteActionDto::Fulfill {
+        status,
+        headers,
+        body,
+        body_base64,
+      } => {
+        let body = if body_base64 {
+          STANDARD
+            .decode(body.as_str())
+            .map_err(|e| rules_error(format!("Invalid base64 body: {e}")))
+            .map(|_| body)?
+        } else {
+          STANDARD.encode(body)
+        };
+        if !(100..=599).contains(&status) {
+          return Err(rules_error(format!(
+            "Invalid status {status}; expected 100..=599"
+          )));
+        }
+        RouteAction::Fulfill {
+          status,
+          headers: headers
+            .into_iter()
+            .map(|(name, value)| HeaderEntry::new(name, value))
+            .collect(),
+          body: Binary::from(body),
+        }
+      }
+      RouteActionDto::Abort { error } => {
+        let name = error.unwrap_or_else(|| "Failed".to_string());
+        RouteAction::Abort(
+          parse_cdp_name::<ErrorReason>(name.as_str())
+            .ok_or_else(|| rules_error(format!("Unknown network error '{name}'")))?,
+        )
+      }
+      RouteActionDto::Continue { headers, post_data } => RouteAction::Continue {
+        headers,
+        post_data: post_data.map(|data| Binary::from(STANDARD.encode(data))),
+      },
+    };
+
+    Ok(Self {
+      id: Uuid::new_v4().to_string(),
+      url: Regex::new(glob_to_regex(dto.url.as_str()).as_str())
+        .map_err(|e| rules_error(format!("Invalid URL pattern '{}': {e}", dto.url)))?,
+      method: dto.method.map(|method| method.to_uppercase()),
+      action,
+    })
+  }
+
+  fn matches(&self, event: &EventRequestPaused) -> bool {
+    self
+      .method
+      .as_ref()
+      .is_none_or(|method| *method == event.request.method.to_uppercase())
+      && self.url.is_match(event.request.url.as_str())
+  }
+}
+
+/// Request interception state of a tab, shared with its interception task.
+pub struct Interception {
+  proxy_credentials: Option<Credentials>,
+  http_credentials: RwLock<Option<Credentials>>,
+  block: RwLock<BlockRules>,
+  blocked: AtomicU64,
+  routes: RwLock<Vec<Route>>,
+}
+
+impl Interception {
+  #[must_use]
+  pub fn new(
+    proxy_credentials: Option<Credentials>,
+    http_credentials: Option<Credentials>,
+    block: BlockRules,
+  ) -> Self {
+    Self {
+      proxy_credentials,
+      http_credentials: RwLock::new(http_credentials),
+      block: RwLock::new(block),
+      blocked: AtomicU64::new(0),
+      routes: RwLock::new(Vec::new()),
+    }
+  }
+
+  /// Replaces the credentials answered to server auth challenges; `None` stops answering them.
+  pub fn set_http_credentials(&self, credentials: Option<Credentials>) {
+    *self
+      .http_credentials
+      .write()
+      .unwrap_or_else(PoisonError::into_inner) = credentials;
+  }
+
+  /// Replaces the blocking rules; the blocked request count is kept.
+  pub fn set_block(&self, block: BlockRules) {
+    *self.block.write().unwrap_or_else(PoisonError::into_inner) = block;
+  }
+
+  /// Returns the current rules with the number of requests blocked so far.
+  #[must_use]
+  pub fn describe(&self) -> NetworkRulesDto {
+    NetworkRulesDto {
+      block: self
+        .block
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .dto
+        .clone(),
+      blocked: self.blocked.load(Ordering::Relaxed),
+    }
+  }
+
+  /// Adds a route rule and returns its ID; later routes take precedence.
+  pub fn add_route(&self, route: Route) -> String {
+    let route_id = route.id.clone();
+    self
+      .routes
+      .write()
+      .unwrap_or_else(PoisonError::into_inner)
+      .push(route);
+    route_id
+  }
+
+  /// Removes the route rule with the given ID, or every route rule without one.
+  /// Returns whether anything was removed.
+  pub fn remove_routes(&self, route_id: Option<&str>) -> bool {
+    let mut routes = self.routes.write().unwrap_or_else(PoisonError::into_inner);
+    let before = routes.len();
+    match route_id {
+      Some(route_id) => routes.retain(|route| route.id != route_id),
+      None => routes.clear(),
+    }
+    routes.len() < before
+  }
+
+  fn route(&self, event: &EventRequestPaused) -> Option<RouteAction> {
+    self
+      .routes
+      .read()
+      .unwrap_or_else(PoisonError::into_inner)
+      .iter()
+      .rev()
+      .find(|route| route.matches(event))
+      .map(|route| route.action.clone())
+  }
+
+  fn credentials(&self, event: &EventAuthRequired) -> Option<Credentials> {
+    if event.auth_challenge.source == Some(AuthChallengeSource::Proxy) {
+      self.proxy_credentials.clone()
+    } else {
+      self
+        .http_credentials
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+    }
+  }
+
+  fn blocks(&self, event: &EventRequestPaused) -> bool {
+    self
+      .block
+      .read()
+      .unwrap_or_else(PoisonError::into_inner)
+      .blocks(event)
+  }
+}
+
+/// The request interception of a tab; stops when dropped together with the tab record.
+pub struct Interceptor {
+  pub interception: Arc<Interception>,
+  _task: TabTask,
+}
+
+/// Starts intercepting the requests of a page.
+///
+/// # Behavior
+///
+/// - Enables the `Fetch` domain for every request of the page, with auth challenges.
+/// - Handles requests matching a route rule (newest first) as the route says: fulfills them
+///   with a canned response, fails them, or continues them with modified headers or body.
+/// - Fails other requests matching the blocking rules with `BlockedByClient` and counts them.
+/// - Continues other paused requests unchanged.
+/// - Resumes a request whose route or blocking action fails all the same: continued
+///   unchanged after a failed fulfill or continue, failed after a failed abort or block.
+/// - Answers auth challenges (`Fetch.authRequired`) with the proxy credentials or, for
+///   servers, the HTTP credentials once per request and cancels repeated challenges, so
+///   wrong credentials fail instead of looping. Only the latest
+///   `MAX_ATTEMPTED_CHALLENGES` answered challenges are remembered.
+/// - Leaves challenges without matching credentials to the browser's default behavior.
+///
+/// The interception runs as a background task that is aborted when the returned
+/// `Interceptor` is dropped; the rules can be changed through its shared state.
+///
+/// # Errors
+///
+/// Returns an `Error` if subscribing to the `Fetch` events or enabling the domain fails.
+///
+/// # Examples
+///
+/// ```ignore
+/// let interceptor =
+///   network::intercept(chaser.raw_page(), Interception::new(proxy, None, rules)).await?;
+/// ```
+pub async fn intercept(page: &Page, interception: Interception) -> Result<Interceptor, Error> {
+  enum Paused {
+    Request(Arc<EventRequestPaused>),
+    Auth(Arc<EventAuthRequired>),
+  }
+
+  fn interception_error(e: impl std::fmt::Display) -> Error {
+    Error::Operation(ErrorInfo {
+      message: format!("Failed to intercept requests: {e}"),
+      code: None,
+    })
+  }
+  fn answer(
+    event: &EventAuthRequired,
+    credentials: Option<Credentials>,
+    attempted: &mut VecDeque<(bool, String)>,
+  ) -> AuthChallengeResponse {
+    let is_proxy = event.auth_challenge.source == Some(AuthChallengeSource::Proxy);
+    let challenge = (is_proxy, event.request_id.inner().clone());
+    // A repeated challenge is answered for the last time, so it is forgotten right away.
+    let repeated = attempted
+      .iter()
+      .position(|attempt| *attempt == challenge)
+      .and_then(|index| attempted.remove(index))
+      .is_some();
+
+    match credentials {
+      Some(_) if repeated => AuthChallengeResponse::new(AuthChallengeResponseResponse::CancelAuth),
+      Some(credentials) => {
+        if attempted.len() >= MAX_ATTEMPTED_CHALLENGES {
+          attempted.pop_front();
+        }
+        attempted.push_back(challenge);
+        AuthChallengeResponse {
+          username: Some(credentials.username),
+          password: Some(credentials.password),
+          ..AuthChallengeResponse::new(AuthChallengeResponseResponse::ProvideCredentials)
+        }
+      }
+      None => AuthChallengeResponse::new(AuthChallengeResponseResponse::Default),
+    }
+  }
+  fn merge_headers(original: &Headers, overrides: &HashMap<String, String>) -> Vec<HeaderEntry> {
+    let original = original
+      .inner()
+      .as_object()
+      .into_iter()
+      .flatten()
+      .filter(|(name, _)| {
+        !overrides
+          .keys()
+          .any(|override_name| override_name.eq_ignore_ascii_case(name))
+      })
+      .map(|(name, value)| HeaderEntry::new(name.clone(), value.as_str().unwrap_or_default()));
+
+    original
+      .chain(
+        overrides
+          .iter()
+          .map(|(name, value)| HeaderEntry::new(name.clone(), value.clone())),
+      )
+      .collect()
+  }
+  async fn resume(
+    page: &Page,
+    interception: &Interception,
+    event: &EventRequestPaused,
+  ) -> Result<(), CdpError> {
+    /// How a paused request is resumed when its route or blocking action fails.
+    enum Fallback {
+      Continue,
+      Fail,
+    }
+
+    let request_id = event.request_id.clone();
+
+    let (resumed, fallback) = match interception.route(event) {
+      Some(RouteAction::Fulfill {
+        status,
+        headers,
+        body,
+      }) => {
+        let params = FulfillRequestParams {
+          response_headers: Some(headers),
+          body: Some(body),
+          ..FulfillRequestParams::new(request_id.clone(), status)
+        };
+        (
+          page.execute(params).await.map(|_| ()),
+          Some(Fallback::Continue),
+        )
+      }
+      Some(RouteAction::Abort(reason)) => (
+        page
+          .execute(FailRequestParams::new(request_id.clone(), reason))
+          .await
+          .map(|_| ()),
+        Some(Fallback::Fail),
+      ),
+      Some(RouteAction::Continue { headers, post_data }) => {
+        let params = ContinueRequestParams {
+          headers: (!headers.is_empty()).then(|| merge_headers(&event.request.headers, &headers)),
+          post_data,
+          ..ContinueRequestParams::new(request_id.clone())
+        };
+        (
+          page.execute(params).await.map(|_| ()),
+          Some(Fallback::Continue),
+        )
+      }
+      None if interception.blocks(event) => {
+        interception.blocked.fetch_add(1, Ordering::Relaxed);
+        (
+          page
+            .execute(FailRequestParams::new(
+              request_id.clone(),
+              ErrorReason::BlockedByClient,
+            ))
+            .await
+            .map(|_| ()),
+          Some(Fallback::Fail),
+        )
+      }
+      None => (
+        page
+          .execute(ContinueRequestParams::new(request_id.clone()))
+          .await
+          .map(|_| ()),
+        None,
+      ),
+    };
+
+    // A request that stays paused hangs the page, so a failed action still resumes it.
+    match (resumed, fallback) {
+      (Err(e), Some(fallback)) => {
+        tracing::warn!(
+          "Failed to handle the intercepted request {}: {e}; resuming it unchanged",
+          event.request.url
+        );
+        match fallback {
+          Fallback::Continue => page
+            .execute(ContinueRequestParams::new(request_id))
+            .await
+            .map(|_| ()),
+          Fallback::Fail => page
+            .execute(FailRequestParams::new(request_id, ErrorReason::Failed))
+            .await
+            .map(|_| ()),
+        }
+      }
+      (resumed, _) => resumed,
+    }
+  }
+
+  let requests = page
+    .event_listener::<EventRequestPaused>()
+    .await
+    .map_err(interception_error)?;
+  let challenges = page
+    .event_listener::<EventAuthRequired>()
+    .await
+    .map_err(interception_error)?;
+
+  let params = EnableParams {
+    patterns: Some(vec![RequestPattern {
+      url_pattern: Some("*".to_string()),
+      ..RequestPattern::default()
+    }]),
+    handle_auth_requests: Some(true),
+  };
+  page.execute(params).await.map_err(interception_error)?;
+
+  let interception = Arc::new(interception);
+  let state = interception.clone();
+  let page = page.clone();
+  let task = tokio::spawn(async move {
+    let mut attempted = VecDeque::with_capacity(MAX_ATTEMPTED_CHALLENGES);
+    let mut events = stream::select(requests.map(Paused::Request), challenges.map(Paused::Auth));
+
+    while let Some(event) = events.next().await {
+      let resumed = match event {
+        Paused::Request(event) => resume(&page, &state, &event).await,
+        Paused::Auth(event) => {
+          let response = answer(&event, state.credentials(&event), &mut attempted);
+          page
+            .execute(ContinueWithAuthParams::new(
+              event.request_id.clone(),
+              response,
+            ))
+            .await
+            .map(|_| ())
+        }
+      };
+
+      if let Err(e) = resumed {
+        tracing::warn!("Failed to resume an intercepted request: {e}");
+      }
+    }
+  });
+
+  Ok(Interceptor {
+    interception,
+    _task: TabTask::new(task.abort_handle()),
+  })
+}
+
+/// Listens for the first network response matching a URL glob and method.
+///
+/// Armed before the action that triggers the request, so the response cannot be missed.
+pub struct ResponseWaiter {
+  page: Page,
+  url: Regex,
+  method: Option<String>,
+  requests: EventStream<EventRequestWillBeSent>,
+  responses: EventStream<EventResponseReceived>,
+  finished: EventStream<EventLoadingFinished>,
+  failed: EventStream<EventLoadingFailed>,
+}
+
+impl ResponseWaiter {
+  /// Subscribes to the `Network` events of a page.
+  ///
+  /// # Errors
+  ///
+  /// Returns an `Error` if the URL glob is invalid or subscribing to the events fails.
+  pub async fn arm(page: &Page, url: &str, method: Option<String>) -> Result<Self, Error> {
+    fn listen_error(e: impl std::fmt::Display) -> Error {
+      Error::Operation(ErrorInfo {
+        message: format!("Failed to listen for responses: {e}"),
+        code: None,
+      })
+    }
+
+    let pattern = Regex::new(glob_to_regex(url).as_str())
+      .map_err(|e| rules_error(format!("Invalid URL pattern '{url}': {e}")))?;
+
+    Ok(Self {
+      page: page.clone(),
+      url: pattern,
+      method: method.map(|method| method.to_uppercase()),
+      requests: page.event_listener().await.map_err(listen_error)?,
+      responses: page.event_listener().await.map_err(listen_error)?,
+      finished: page.event_listener().await.map_err(listen_error)?,
+      failed: page.event_listener().await.map_err(listen_error)?,
+    })
+  }
+
+  /// Waits for the first matching response to finish loading and reads its body.
+  ///
+  /// # Behavior
+  ///
+  /// - Matches responses by URL and by the method of their request.
+  /// - Returns text bodies as is; binary bodies stay base64-encoded with
+  ///   `base64_encoded` set.
+  ///
+  /// # Errors
+  ///
+  /// Returns an `Error` if the matching request fails, its body cannot be read or the
+  /// page goes away. Waiting is unbounded; callers apply their own timeout.
+  pub async fn wait(self) -> Result<NetworkResponseDto, Error> {
+    enum Event {
+      Request(Arc<EventRequestWillBeSent>),
+      Response(Arc<EventResponseReceived>),
+      Finished(Arc<EventLoadingFinished>),
+      Failed(Arc<EventLoadingFailed>),
+    }
+
+    let Self {
+      page,
+      url,
+      method,
+      requests,
+      responses,
+      finished,
+      failed,
+    } = self;
+
+    let mut events = stream::select(
+      stream::select(requests.map(Event::Request), responses.map(Event::Response)),
+      stream::select(finished.map(Event::Finished), failed.map(Event::Failed)),
+    );
+    let mut methods = HashMap::new();
+    let mut matched: Option<Arc<EventResponseReceived>> = None;
+    let is_matched = |matched: &Option<Arc<EventResponseReceived>>, request_id: &str| {
+      matched
+        .as_ref()
+        .is_some_and(|response| response.request_id.inner() == request_id)
+    };
+
+    while let Some(event) = events.next().await {
+      match event {
+        Event::Request(event) => {
+          methods.insert(
+            event.request_id.inner().clone(),
+            event.request.method.to_uppercase(),
+          );
+        }
+        Event::Response(event) if matched.is_none() => {
+          let method_matches = method
+            .as_ref()
+            .is_none_or(|method| methods.get(event.request_id.inner()) == Some(method));
+          if method_matches && url.is_match(event.response.url.as_str()) {
+            matched = Some(event);
+          }
+        }
+        Event::Finished(event) if is_matched(&matched, event.request_id.inner()) => {
+          let response = matched.take().map(|matched| matched.response.clone());
+          return read_response(&page, event.request_id.clone(), response).await;
+        }
+        Event::Failed(event) if is_matched(&matched, event.request_id.inner()) => {
+          return Err(Error::Operation(ErrorInfo {
+            message: format!("Matching request failed: {}", event.error_text),
+            code: None,
+          }));
+        }
+        _ => {}
+      }
+    }
+
+    Err(Error::Operation(ErrorInfo {
+      message: "The page closed before a matching response arrived".to_string(),
+      code: None,
+    }))
+  }
+}
+
+async fn read_response(
+  page: &Page,
+  request_id: RequestId,
+  response: Option<Response>,
+) -> Result<NetworkResponseDto, Error> {
+  let body = page
+    .execute(GetResponseBodyParams::new(request_id))
+    .await
+    .map(|response| response.result)
+    .map_err(|e| {
+      Error::Operation(ErrorInfo {
+        message: format!("Failed to read the response body: {e}"),
+        code: None,
+      })
+    })?;
+
+  // Text that arrives base64-encoded (e.g. after interception) is decoded when it is UTF-8.
+  let (body, base64_encoded) = if body.base64_encoded {
+    STANDARD
+      .decode(body.body.as_str())
+      .ok()
+      .and_then(|bytes| String::from_utf8(bytes).ok())
+      .map_or((body.body, true), |text| (text, false))
+  } else {
+    (body.body, false)
+  };
+
+  Ok(NetworkResponseDto {
+    url: response
+      .as_ref()
+      .map(|response| response.url.clone())
+      .unwrap_or_default(),
+    status: response.as_ref().map_or(0, |response| response.status),
+    headers: response
+      .as_ref()
+      .and_then(|response| response.headers.inner().as_object())
+      .map(|headers| {
+        headers
+          .iter()
+          .filter_map(|(name, value)| {
+            value
+              .as_str()
+              .map(|value| (name.clone(), value.to_string()))
+          })
+          .collect()
+      })
+      .unwrap_or_default(),
+    body,
+    base64_encoded,
+  })
+}
+
+/// Converts a glob where `*` matches any characters and `?` exactly one into an anchored
+/// regular expression; `\\` escapes the next character and a trailing `\\` matches itself.
+fn glob_to_regex(glob: &str) -> String {
+  let mut pattern = String::from("^");
+  let mut chars = glob.chars();
+  while let Some(c) = chars.next() {
+    match c {
+      '*' => pattern.push_str(".*"),
+      '?' => pattern.push('.'),
+      '\\' => {
+        let escaped = chars.next().unwrap_or('\\');
+        pattern.push_str(&regex::escape(escaped.encode_utf8(&mut [0; 4])));
+      }
+      c => pattern.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+    }
+  }
+  pattern.push('$');
+  pattern
+}
+
+/// Parses a CDP enum value by its protocol name, case-insensitively.
+fn parse_cdp_name<T: FromStr>(name: &str) -> Option<T> {
+  T::from_str(name)
+    .or_else(|_| T::from_str(name.to_lowercase().as_str()))
+    .ok()
+}
+
+fn rules_error(message: String) -> Error {
+  Error::Operation(ErrorInfo {
+    message,
+    code: None,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn glob_matches(glob: &str, url: &str) -> bool {
+    Regex::new(glob_to_regex(glob).as_str())
+      .expect("glob compiles")
+      .is_match(url)
+  }
+
+  #[test]
+  fn glob_star_matches_any_characters_across_the_whole_url() {
+    assert!(glob_matches(
+      "*.doubleclick.net/*",
+      "https://ad.doubleclick.net/x?y=1"
+    ));
+    assert!(glob_matches("*", ""));
+    assert!(!glob_matches(
+      "*.doubleclick.net",
+      "https://ad.doubleclick.net/x"
+    ));
+  }
+
+  #[test]
+  fn glob_question_mark_matches_exactly_one_character() {
+    assert!(glob_matches("https://a.test/?.js", "https://a.test/1.js"));
+    assert!(!glob_matches("https://a.test/?.js", "https://a.test/.js"));
+    assert!(!glob_matches("https://a.test/?.js", "https://a.test/12.js"));
+  }
+
+  #[test]
+  fn glob_escapes_regex_metacharacters() {
+    assert!(glob_matches(
+      "https://a.test/(x)+[y]",
+      "https://a.test/(x)+[y]"
+    ));
+    assert!(!glob_matches("https://a.test/a.b", "https://a.test/axb"));
+  }
+
+  #[test]
+  fn glob_backslash_escapes_the_next_character() {
+    assert!(glob_matches(r"https://a.test/\*", "https://a.test/*"));
+    assert!(!glob_matches(r"https://a.test/\*", "https://a.test/x"));
+    assert!(glob_matches(r"https://a.test/\?", "https://a.test/?"));
+    assert!(!glob_matches(r"https://a.test/\?", "https://a.test/x"));
+    assert!(glob_matches(r"a\\b", r"a\b"));
+  }
+
+  #[test]
+  fn glob_trailing_backslash_matches_itself() {
+    assert!(glob_matches(r"https://a.test/\", r"https://a.test/\"));
+    assert!(!glob_matches(r"https://a.test/\", "https://a.test/"));
+  }
+
+  #[test]
+  fn cdp_names_parse_case_insensitively() {
+    assert_eq!(
+      parse_cdp_name::<ResourceType>("Image"),
+      Some(ResourceType::Image)
+    );
+    assert_eq!(
+      parse_cdp_name::<ResourceType>("image"),
+      Some(ResourceType::Image)
+    );
+    assert_eq!(
+      parse_cdp_name::<ResourceType>("IMAGE"),
+      Some(ResourceType::Image)
+    );
+    assert_eq!(
+      parse_cdp_name::<ResourceType>("XHR"),
+      Some(ResourceType::Xhr)
+    );
+    assert_eq!(
+      parse_cdp_name::<ErrorReason>("blockedByClient"),
+      Some(ErrorReason::BlockedByClient)
+    );
+  }
+
+  #[test]
+  fn cdp_names_reject_unknown_names() {
+    assert_eq!(parse_cdp_name::<ResourceType>("picture"), None);
+    assert_eq!(parse_cdp_name::<ResourceType>(""), None);
+    assert_eq!(parse_cdp_name::<ErrorReason>("Nope"), None);
+  }
+
+  #[test]
+  fn block_rules_compile_resource_types_and_urls() {
+    let rules = BlockRules::compile(BlockDto {
+      resource_types: vec!["Image".to_string(), "font".to_string()],
+      url_patterns: vec!["*.ads.test/*".to_string()],
+      url_regexes: vec!["tracker".to_string()],
+    })
+    .expect("rules compile");
+
+    assert_eq!(
+      rules.resource_types,
+      vec![ResourceType::Image, ResourceType::Font]
+    );
+    assert_eq!(rules.urls.len(), 2);
+    assert!(rules.urls[0].is_match("https://x.ads.test/banner.png"));
+    assert!(rules.urls[1].is_match("https://cdn.test/tracker.js"));
+  }
+
+  #[test]
+  fn block_rules_reject_unknown_resource_types() {
+    let compiled = BlockRules::compile(BlockDto {
+      resource_types: vec!["picture".to_string()],
+      ..BlockDto::default()
+    });
+
+    assert!(compiled.is_err_and(|e| e.to_string().contains("Unknown resource type 'picture'")));
+  }
+
+  #[test]
+  fn block_rules_reject_invalid_regexes() {
+    let compiled = BlockRules::compile(BlockDto {
+      url_regexes: vec!["(".to_string()],
+      ..BlockDto::default()
+    });
+
+    assert!(compiled.is_err_and(|e| e.to_string().contains("Invalid URL pattern '('")));
+  }
+
+  #[test]
+  fn routes_reject_fulfill_status_outside_the_http_range() {
+    let fulfill = |status| RouteDto {
+      url: "*".to_string(),
+      method: None,
+      action: RouteActionDto::Fulfill {
+        status,
+        headers: HashMap::new(),
+        body: String::new(),
+        body_base64: false,
+      },
+    };
+
+    assert!(Route::compile(fulfill(0)).is_err());
+    assert!(Route::compile(fulfill(99)).is_err());
+    assert!(Route::compile(fulfill(1000)).is_err());
+    assert!(Route::compile(fulfill(100)).is_ok());
+    assert!(Route::compile(fulfill(599)).is_ok());
+  }
+}
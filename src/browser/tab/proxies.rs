@@ -0,0 +1,397 @@
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex, MutexGuard};
+use std::time::{Duration, SystemTime};
+
+use crate::browser::tab::dto::{PoolStrategyDto, ProxyDto, ProxyPoolDto, ProxyStatsDto};
+use crate::browser::tab::models::{ProxyPoolConfig, to_unix_millis};
+use crate::models::Error;
+
+/// A proxy of the pool with its usage and health counters.
+struct Entry {
+  proxy: ProxyDto,
+  in_use: usize,
+  uses: u64,
+  successes: u64,
+  failures: u64,
+  consecutive_failures: u32,
+  cooldown_until: Option<SystemTime>,
+}
+
+impl Entry {
+  fn is_healthy(&self, now: SystemTime) -> bool {
+    self.cooldown_until.is_none_or(|until| until <= now)
+  }
+}
+
+struct Pool {
+  entries: Vec<Entry>,
+  strategy: PoolStrategyDto,
+  max_failures: u32,
+  cooldown: Duration,
+  next: usize,
+  sticky: HashMap<String, usize>,
+}
+
+impl Pool {
+  fn new(config: ProxyPoolConfig) -> Self {
+    Self {
+      entries: config
+        .proxies
+        .into_iter()
+        .map(|proxy| Entry {
+          proxy,
+          in_use: 0,
+          uses: 0,
+          successes: 0,
+          failures: 0,
+          consecutive_failures: 0,
+          cooldown_until: None,
+        })
+        .collect(),
+      strategy: config.strategy,
+      max_failures: config.max_failures.max(1),
+      cooldown: Duration::from_secs(config.cooldown),
+      next: 0,
+      sticky: HashMap::new(),
+    }
+  }
+
+  /// Selects a healthy proxy for the request and counts it as in use; see `draw`.
+  fn draw(&mut self, request: &ProxyPoolDto, now: SystemTime) -> Result<usize, Error> {
+    fn least_used(entries: &[Entry], now: SystemTime) -> Option<usize> {
+      entries
+        .iter()
+        .enumerate()
+        .filter(|(_, entry)| entry.is_healthy(now))
+        .min_by_key(|(_, entry)| (entry.in_use, entry.uses))
+        .map(|(index, _)| index)
+    }
+
+    if self.entries.is_empty() {
+      return Err(Error::NotSupported(
+        "proxy pool is not configured".to_string(),
+      ));
+    }
+
+    let selected = match request.strategy.unwrap_or(self.strategy) {
+      PoolStrategyDto::RoundRobin => {
+        let len = self.entries.len();
+        let selected = (0..len)
+          .map(|offset| (self.next + offset) % len)
+          .find(|index| self.entries[*index].is_healthy(now));
+        if let Some(index) = selected {
+          self.next = (index + 1) % len;
+        }
+        selected
+      }
+      PoolStrategyDto::LeastUsed => least_used(&self.entries, now),
+      PoolStrategyDto::Sticky => {
+        let key = request.sticky_key.clone().ok_or_else(|| {
+          Error::NotSupported("sticky proxy strategy requires sticky_key".to_string())
+        })?;
+        let selected = self
+          .sticky
+          .get(&key)
+          .copied()
+          .filter(|index| self.entries[*index].is_healthy(now))
+          .or_else(|| least_used(&self.entries, now));
+        if let Some(index) = selected {
+          self.sticky.insert(key, index);
+        }
+        selected
+      }
+    };
+
+    let Some(index) = selected else {
+      let retry_after = self
+        .entries
+        .iter()
+        .filter_map(|entry| entry.cooldown_until)
+        .min()
+        .and_then(|until| until.duration_since(now).ok())
+        .map_or(1, |remaining| remaining.as_secs().max(1));
+      return Err(Error::Exhausted {
+        message: "Every proxy of the pool is cooling down".to_string(),
+        retry_after,
+      });
+    };
+
+    let entry = &mut self.entries[index];
+    entry.in_use += 1;
+    entry.uses += 1;
+    Ok(index)
+  }
+
+  /// Records the outcome of a navigation through the proxy at `index`; see `report`.
+  fn report(&mut self, index: usize, succeeded: bool, now: SystemTime) {
+    let (max_failures, cooldown) = (self.max_failures, self.cooldown);
+    let Some(entry) = self.entries.get_mut(index) else {
+      return;
+    };
+
+    if succeeded {
+      entry.successes += 1;
+      entry.consecutive_failures = 0;
+      entry.cooldown_until = None;
+      return;
+    }
+
+    entry.failures += 1;
+    entry.consecutive_failures += 1;
+    if entry.consecutive_failures >= max_failures {
+      entry.cooldown_until = Some(now + cooldown);
+      tracing::warn!(
+        "Proxy {} is cooling down after {} failed navigations",
+        entry.proxy.server,
+        entry.consecutive_failures
+      );
+    }
+  }
+
+  fn release(&mut self, index: usize) {
+    if let Some(entry) = self.entries.get_mut(index) {
+      entry.in_use = entry.in_use.saturating_sub(1);
+    }
+  }
+}
+
+static POOL: LazyLock<Mutex<Pool>> =
+  LazyLock::new(|| Mutex::new(Pool::new(ProxyPoolConfig::from_env())));
+
+fn pool() -> MutexGuard<'static, Pool> {
+  POOL
+    .lock()
+    .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// A proxy drawn from the pool; counts as in use until dropped.
+pub struct ProxyLease {
+  index: usize,
+}
+
+impl Drop for ProxyLease {
+  fn drop(&mut self) {
+    pool().release(self.index);
+  }
+}
+
+/// Draws a healthy proxy from the pool configured with `PROXY_POOL_FILE`.
+///
+/// # Behavior
+///
+/// - Uses `request.strategy`, or the strategy of the pool configuration:
+///   - `round_robin`: the next healthy proxy after the previously drawn one.
+///   - `least_used`: the healthy proxy with the fewest open tabs, then the fewest uses.
+///   - `sticky`: the proxy previously drawn for `request.sticky_key` while it stays
+///     healthy; otherwise the least used one, which becomes the new sticky proxy.
+/// - Skips proxies that are cooling down after navigation failures.
+/// - The proxy counts as in use until the returned lease is dropped.
+///
+/// # Errors
+///
+/// Returns an `Error` if:
+/// - No proxy pool is configured.
+/// - The `sticky` strategy is used without `sticky_key`.
+/// - Every proxy is cooling down (`Error::Exhausted`, retry after the earliest cooldown ends).
+///
+/// # Examples
+///
+/// ```ignore
+/// let (proxy, lease) = proxies::draw(&ProxyPoolDto::default())?;
+/// ```
+pub fn draw(request: &ProxyPoolDto) -> Result<(ProxyDto, ProxyLease), Error> {
+  let mut pool = pool();
+  let index = pool.draw(request, SystemTime::now())?;
+  Ok((pool.entries[index].proxy.clone(), ProxyLease { index }))
+}
+
+/// Records the outcome of a navigation through a pooled proxy.
+///
+/// # Behavior
+///
+/// - A success resets the consecutive failure count.
+/// - A failure that reaches the configured `max_failures` in a row puts the proxy into
+///   cooldown. After the cooldown a single further failure cools it down again.
+///
+/// # Examples
+///
+/// ```ignore
+/// proxies::report(&lease, result.is_ok());
+/// ```
+pub fn report(lease: &ProxyLease, succeeded: bool) {
+  pool().report(lease.index, succeeded, SystemTime::now());
+}
+
+/// Returns the usage and health of every proxy of the pool.
+///
+/// # Examples
+///
+/// ```ignore
+/// let proxies = proxies::stats();
+/// ```
+pub fn stats() -> Vec<ProxyStatsDto> {
+  let now = SystemTime::now();
+
+  pool()
+    .entries
+    .iter()
+    .map(|entry| ProxyStatsDto {
+      server: entry.proxy.server.clone(),
+      healthy: entry.is_healthy(now),
+      in_use: entry.in_use,
+      uses: entry.uses,
+      successes: entry.successes,
+      failures: entry.failures,
+      cooldown_until: entry
+        .cooldown_until
+        .filter(|until| *until > now)
+        .map(to_unix_millis),
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn pool_of(size: usize, strategy: PoolStrategyDto) -> Pool {
+    Pool::new(ProxyPoolConfig {
+      strategy,
+      max_failures: 2,
+      cooldown: 60,
+      proxies: (0..size)
+        .map(|index| ProxyDto {
+          server: format!("http://proxy-{index}.test:8080"),
+          username: None,
+          password: None,
+          bypass: None,
+        })
+        .collect(),
+    })
+  }
+
+  fn sticky(key: &str) -> ProxyPoolDto {
+    ProxyPoolDto {
+      strategy: Some(PoolStrategyDto::Sticky),
+      sticky_key: Some(key.to_string()),
+    }
+  }
+
+  #[test]
+  fn round_robin_cycles_through_the_proxies() {
+    let mut pool = pool_of(3, PoolStrategyDto::RoundRobin);
+    let now = SystemTime::now();
+
+    let drawn = (0..4)
+      .map(|_| {
+        pool
+          .draw(&ProxyPoolDto::default(), now)
+          .expect("proxy drawn")
+      })
+      .collect::<Vec<_>>();
+
+    assert_eq!(drawn, [0, 1, 2, 0]);
+    assert_eq!(pool.entries[0].uses, 2);
+    assert_eq!(pool.entries[0].in_use, 2);
+  }
+
+  #[test]
+  fn least_used_prefers_fewest_open_tabs_then_fewest_uses() {
+    let mut pool = pool_of(2, PoolStrategyDto::LeastUsed);
+    let now = SystemTime::now();
+
+    assert_eq!(pool.draw(&ProxyPoolDto::default(), now).ok(), Some(0));
+    assert_eq!(pool.draw(&ProxyPoolDto::default(), now).ok(), Some(1));
+
+    pool.release(0);
+    assert_eq!(pool.draw(&ProxyPoolDto::default(), now).ok(), Some(0));
+
+    pool.release(0);
+    pool.release(1);
+    pool.entries[0].uses = 5;
+    assert_eq!(pool.draw(&ProxyPoolDto::default(), now).ok(), Some(1));
+  }
+
+  #[test]
+  fn sticky_keeps_the_proxy_of_a_key_while_it_is_healthy() {
+    let mut pool = pool_of(2, PoolStrategyDto::RoundRobin);
+    let now = SystemTime::now();
+
+    let first = pool.draw(&sticky("customer-a"), now).expect("proxy drawn");
+    let other = pool.draw(&sticky("customer-b"), now).expect("proxy drawn");
+    assert_ne!(first, other);
+    assert_eq!(pool.draw(&sticky("customer-a"), now).ok(), Some(first));
+
+    pool.report(first, false, now);
+    pool.report(first, false, now);
+    assert_eq!(pool.draw(&sticky("customer-a"), now).ok(), Some(other));
+    assert_eq!(pool.sticky.get("customer-a"), Some(&other));
+  }
+
+  #[test]
+  fn sticky_requires_a_key() {
+    let mut pool = pool_of(1, PoolStrategyDto::Sticky);
+
+    let drawn = pool.draw(&ProxyPoolDto::default(), SystemTime::now());
+
+    assert!(matches!(drawn, Err(Error::NotSupported(_))));
+  }
+
+  #[test]
+  fn consecutive_failures_cool_a_proxy_down_until_the_cooldown_ends() {
+    let mut pool = pool_of(2, PoolStrategyDto::RoundRobin);
+    let now = SystemTime::now();
+
+    pool.report(0, false, now);
+    assert!(pool.entries[0].is_healthy(now));
+    pool.report(0, false, now);
+    assert!(!pool.entries[0].is_healthy(now));
+
+    assert_eq!(pool.draw(&ProxyPoolDto::default(), now).ok(), Some(1));
+    assert_eq!(pool.draw(&ProxyPoolDto::default(), now).ok(), Some(1));
+
+    let later = now + Duration::from_secs(60);
+    assert!(pool.entries[0].is_healthy(later));
+    assert_eq!(pool.draw(&ProxyPoolDto::default(), later).ok(), Some(0));
+  }
+
+  #[test]
+  fn a_success_resets_the_failure_streak() {
+    let mut pool = pool_of(1, PoolStrategyDto::RoundRobin);
+    let now = SystemTime::now();
+
+    pool.report(0, false, now);
+    pool.report(0, true, now);
+    pool.report(0, false, now);
+
+    assert!(pool.entries[0].is_healthy(now));
+    assert_eq!(pool.entries[0].failures, 2);
+    assert_eq!(pool.entries[0].successes, 1);
+  }
+
+  #[test]
+  fn a_pool_cooling_down_entirely_is_exhausted_until_the_earliest_cooldown_ends() {
+    let mut pool = pool_of(2, PoolStrategyDto::LeastUsed);
+    let now = SystemTime::now();
+
+    pool.report(0, false, now);
+    pool.report(0, false, now);
+    let later = now + Duration::from_secs(20);
+    pool.report(1, false, later);
+    pool.report(1, false, later);
+
+    match pool.draw(&ProxyPoolDto::default(), later) {
+      Err(Error::Exhausted { retry_after, .. }) => assert_eq!(retry_after, 40),
+      _ => panic!("expected the pool to be exhausted"),
+    }
+  }
+
+  #[test]
+  fn an_empty_pool_is_not_supported() {
+    let mut pool = pool_of(0, PoolStrategyDto::RoundRobin);
+
+    let drawn = pool.draw(&ProxyPoolDto::default(), SystemTime::now());
+
+    assert!(matches!(drawn, Err(Error::NotSupported(_))));
+  }
+}
@@ -0,0 +1,251 @@
+use futures::future;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::LazyLock;
+use std::time::SystemTime;
+use tokio::sync::{Mutex, Notify};
+use tokio::time::sleep;
+
+use crate::browser::tab::api;
+use crate::browser::tab::dto::ExpirationsDto;
+use crate::browser::tab::models::to_unix_millis;
+
+/// Pending tab expirations ordered by deadline, with a reverse index by tab ID.
+#[derive(Default)]
+struct Queue {
+  deadlines: BTreeSet<(SystemTime, String)>,
+  by_tab: HashMap<String, SystemTime>,
+}
+
+impl Queue {
+  /// Sets the deadline of a tab, replacing its pending one.
+  fn schedule(&mut self, tab_id: &str, at: SystemTime) {
+    if let Some(previous) = self.by_tab.insert(tab_id.to_string(), at) {
+      self.deadlines.remove(&(previous, tab_id.to_string()));
+    }
+    self.deadlines.insert((at, tab_id.to_string()));
+  }
+
+  fn cancel(&mut self, tab_id: &str) {
+    if let Some(at) = self.by_tab.remove(tab_id) {
+      self.deadlines.remove(&(at, tab_id.to_string()));
+    }
+  }
+
+  /// Removes and returns the tabs due at `now`, earliest deadline first.
+  fn take_due(&mut self, now: SystemTime) -> Vec<String> {
+    let mut due = Vec::new();
+    while self.deadlines.first().is_some_and(|(at, _)| *at <= now) {
+      if let Some((_, tab_id)) = self.deadlines.pop_first() {
+        self.by_tab.remove(&tab_id);
+        due.push(tab_id);
+      }
+    }
+    due
+  }
+}
+
+static QUEUE: LazyLock<Mutex<Queue>> = LazyLock::new(|| Mutex::new(Queue::default()));
+static WAKE: Notify = Notify::const_new();
+
+/// Schedules (or reschedules) the expiration of a tab at the given deadline.
+///
+/// # Behavior
+///
+/// - Replaces any pending expiration of the same tab.
+/// - Wakes the reaper so an earlier deadline is not missed.
+///
+/// # Examples
+///
+/// ```ignore
+/// reaper::schedule(&tab_id, tab.due_at()).await;
+/// ```
+pub async fn schedule(tab_id: &str, at: SystemTime) {
+  QUEUE.lock().await.schedule(tab_id, at);
+  WAKE.notify_one();
+}
+
+/// Cancels the pending expiration of a tab, if any.
+///
+/// # Examples
+///
+/// ```ignore
+/// reaper::cancel(&tab_id).await;
+/// ```
+pub async fn cancel(tab_id: &str) {
+  QUEUE.lock().await.cancel(tab_id);
+}
+
+/// Returns counts of pending expirations for diagnostics.
+///
+/// # Examples
+///
+/// ```ignore
+/// let expirations = reaper::stats().await;
+/// ```
+pub async fn stats() -> ExpirationsDto {
+  let now = SystemTime::now();
+  let queue = QUEUE.lock().await;
+
+  ExpirationsDto {
+    pending: queue.deadlines.len(),
+    overdue: queue
+      .deadlines
+      .iter()
+      .take_while(|(at, _)| *at <= now)
+      .count(),
+    next_due_at: queue.deadlines.first().map(|(at, _)| to_unix_millis(*at)),
+  }
+}
+
+/// Returns the earliest pending expiration deadline, if any.
+pub async fn next_due_at() -> Option<SystemTime> {
+  QUEUE.lock().await.deadlines.first().map(|(at, _)| *at)
+}
+
+/// Runs the reaper that closes tabs once their deadline is reached.
+///
+/// # Behavior
+///
+/// - Sleeps until the earliest pending deadline or until the queue changes.
+/// - Re-checks the tab's current deadline before closing, since activity or a page action
+///   still in flight may have pushed its idle deadline later; such tabs are put back into
+///   the queue.
+/// - Closes due tabs through `api::close`, so the regular cleanup applies.
+///
+/// Intended to be spawned once as a background task; never returns.
+///
+/// # Examples
+///
+/// ```ignore
+/// tokio::spawn(reaper::run());
+/// ```
+pub async fn run() {
+  async fn next_deadline() -> Option<(SystemTime, String)> {
+    QUEUE.lock().await.deadlines.first().cloned()
+  }
+  async fn reap(tab_id: String) {
+    match api::due_at(&tab_id).await {
+      Some(due_at) if due_at > SystemTime::now() => schedule(&tab_id, due_at).await,
+      Some(_) => match api::close(&tab_id).await {
+        Ok(()) => tracing::info!("Tab {tab_id} expired"),
+        Err(e) => tracing::warn!("Failed to auto-close tab {tab_id} after expiration: {e}"),
+      },
+      None => tracing::debug!("Tab {tab_id} was closed before its expiration"),
+    }
+  }
+
+  loop {
+    match next_deadline().await {
+      Some((at, _)) => {
+        let remaining = at.duration_since(SystemTime::now()).unwrap_or_default();
+        tokio::select! {
+          () = sleep(remaining) => {
+            let due = QUEUE.lock().await.take_due(SystemTime::now());
+            future::join_all(due.into_iter().map(reap)).await;
+          },
+          () = WAKE.notified() => {},
+        }
+      }
+      None => WAKE.notified().await,
+    }
+  }
+}
+
+/// Closes every tab with a pending expiration immediately.
+///
+/// # Behavior
+///
+/// - Empties the expiration queue.
+/// - Closes the affected tabs concurrently, logging failures.
+///
+/// Intended to be called on shutdown so no tab outlives the server.
+///
+/// # Examples
+///
+/// ```ignore
+/// reaper::drain().await;
+/// ```
+pub async fn drain() {
+  async fn close_tab(tab_id: String) {
+    if let Err(e) = api::close(&tab_id).await {
+      tracing::warn!("Failed to close tab {tab_id} on shutdown: {e}");
+    }
+  }
+
+  let tab_ids = {
+    let mut queue = QUEUE.lock().await;
+    queue.deadlines.clear();
+    queue
+      .by_tab
+      .drain()
+      .map(|(tab_id, _)| tab_id)
+      .collect::<Vec<_>>()
+  };
+
+  tracing::info!("Closing {} tabs on shutdown", tab_ids.len());
+
+  future::join_all(tab_ids.into_iter().map(close_tab)).await;
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::time::Duration;
+
+  fn at(seconds: u64) -> SystemTime {
+    SystemTime::UNIX_EPOCH + Duration::from_secs(seconds)
+  }
+
+  #[test]
+  fn take_due_returns_the_tabs_due_by_now_earliest_first() {
+    let mut queue = Queue::default();
+    queue.schedule("late", at(30));
+    queue.schedule("first", at(10));
+    queue.schedule("second", at(20));
+
+    assert_eq!(queue.take_due(at(20)), ["first", "second"]);
+    assert_eq!(queue.take_due(at(20)), Vec::<String>::new());
+    assert_eq!(queue.deadlines.len(), 1);
+    assert_eq!(queue.by_tab.keys().collect::<Vec<_>>(), ["late"]);
+  }
+
+  #[test]
+  fn take_due_includes_a_deadline_exactly_at_now() {
+    let mut queue = Queue::default();
+    queue.schedule("tab", at(10));
+
+    assert_eq!(queue.take_due(at(10)), ["tab"]);
+  }
+
+  #[test]
+  fn schedule_replaces_the_pending_deadline_of_a_tab() {
+    let mut queue = Queue::default();
+    queue.schedule("tab", at(10));
+    queue.schedule("tab", at(40));
+
+    assert_eq!(queue.deadlines.len(), 1);
+    assert!(queue.take_due(at(30)).is_empty());
+    assert_eq!(queue.take_due(at(40)), ["tab"]);
+  }
+
+  #[test]
+  fn schedule_can_bring_a_deadline_forward() {
+    let mut queue = Queue::default();
+    queue.schedule("tab", at(40));
+    queue.schedule("tab", at(10));
+
+    assert_eq!(queue.take_due(at(10)), ["tab"]);
+    assert!(queue.by_tab.is_empty());
+  }
+
+  #[test]
+  fn cancel_removes_the_pending_deadline() {
+    let mut queue = Queue::default();
+    queue.schedule("tab", at(10));
+    queue.schedule("other", at(10));
+    queue.cancel("tab");
+    queue.cancel("unknown");
+
+    assert_eq!(queue.take_due(at(10)), ["other"]);
+  }
+}
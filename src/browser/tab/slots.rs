@@ -0,0 +1,104 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, LazyLock};
+use std::time::SystemTime;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::time::timeout;
+
+use crate::browser::tab::models::{LimitPolicy, TabLimits};
+use crate::browser::tab::reaper;
+use crate::models::Error;
+
+struct Slots {
+  semaphore: Option<Arc<Semaphore>>,
+  policy: LimitPolicy,
+  waiting: AtomicUsize,
+}
+
+static SLOTS: LazyLock<Slots> = LazyLock::new(|| {
+  let limits = TabLimits::from_env();
+  Slots {
+    semaphore: limits.max_tabs.map(|max| Arc::new(Semaphore::new(max))),
+    policy: limits.policy,
+    waiting: AtomicUsize::new(0),
+  }
+});
+
+/// Keeps the number of queued requests accurate even if a waiting request is dropped.
+struct Waiting;
+
+impl Waiting {
+  fn enter(size: usize) -> Option<Self> {
+    SLOTS
+      .waiting
+      .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |waiting| {
+        (waiting < size).then_some(waiting + 1)
+      })
+      .ok()
+      .map(|_| Waiting)
+  }
+}
+
+impl Drop for Waiting {
+  fn drop(&mut self) {
+    SLOTS.waiting.fetch_sub(1, Ordering::SeqCst);
+  }
+}
+
+/// Acquires a slot for a new tab according to the `MAX_TABS` settings.
+///
+/// # Behavior
+///
+/// - Returns `None` when no limit is configured.
+/// - With the `reject` policy, fails immediately if every slot is taken.
+/// - With the `queue` policy, waits in FIFO order for a slot, bounded by the queue size and timeout.
+/// - The slot is released when the returned permit is dropped, i.e. when the tab leaves the registry
+///   or when opening it fails.
+///
+/// # Errors
+///
+/// Returns `Error::Exhausted` with a `retry_after` hint (the time until the next scheduled tab
+/// expiration, at least one second) if no slot could be acquired.
+///
+/// # Examples
+///
+/// ```ignore
+/// let slot = slots::acquire().await?;
+/// ```
+pub async fn acquire() -> Result<Option<OwnedSemaphorePermit>, Error> {
+  async fn exhausted(message: &str) -> Error {
+    let retry_after = reaper::next_due_at()
+      .await
+      .and_then(|at| at.duration_since(SystemTime::now()).ok())
+      .map_or(1, |remaining| remaining.as_secs() + 1);
+
+    Error::Exhausted {
+      message: message.to_string(),
+      retry_after,
+    }
+  }
+
+  let Some(semaphore) = SLOTS.semaphore.clone() else {
+    return Ok(None);
+  };
+
+  match &SLOTS.policy {
+    LimitPolicy::Reject => match semaphore.try_acquire_owned() {
+      Ok(permit) => Ok(Some(permit)),
+      Err(_) => Err(exhausted("Maximum number of open tabs reached").await),
+    },
+    LimitPolicy::Queue {
+      size,
+      timeout: wait,
+    } => {
+      let Some(_waiting) = Waiting::enter(*size) else {
+        return Err(exhausted("Maximum number of open tabs reached and the queue is full").await);
+      };
+
+      match timeout(*wait, semaphore.acquire_owned()).await {
+        Ok(Ok(permit)) => Ok(Some(permit)),
+        Ok(Err(_)) => Err(Error::Canceled("Tab slots are closed".to_string())),
+        Err(_) => Err(exhausted("Timed out waiting for a free tab slot").await),
+      }
+    }
+  }
+}
@@ -19,6 +19,17 @@ pub enum Error {
   NotImplemented(String),
   NotSupported(String),
   Canceled(String),
+  /// An operation did not finish within its time limit.
+  Timeout(String),
+  /// A limited resource is exhausted; the caller may retry after `retry_after` seconds.
+  Exhausted {
+    message: String,
+    retry_after: u64,
+  },
+  /// The tab's renderer crashed; the tab can only be closed.
+  Crashed(String),
+  /// A JavaScript dialog blocks the page until it is answered.
+  Blocked(String),
   Operation(ErrorInfo),
 }
 
@@ -29,6 +40,10 @@ impl std::fmt::Display for Error {
       Error::NotImplemented(msg) => write!(f, "Not Implemented: {msg}"),
       Error::NotSupported(msg) => write!(f, "Not Supported: {msg}"),
       Error::Canceled(msg) => write!(f, "Canceled: {msg}"),
+      Error::Timeout(msg) => write!(f, "Timeout: {msg}"),
+      Error::Exhausted { message, .. } => write!(f, "Exhausted: {message}"),
+      Error::Crashed(msg) => write!(f, "Crashed: {msg}"),
+      Error::Blocked(msg) => write!(f, "Blocked: {msg}"),
       Error::Operation(info) => write!(f, "Operation Error: {info}"),
     }
   }
@@ -1,10 +1,21 @@
 use actix_web::HttpResponse;
+use actix_web::http::header::RETRY_AFTER;
+use serde::Serialize;
 
 use crate::models::Error;
 
 pub fn from_error(e: Error) -> HttpResponse {
   match e {
     Error::NotFound(msg) => HttpResponse::NotFound().body(msg),
+    Error::Timeout(msg) => HttpResponse::GatewayTimeout().body(msg),
+    Error::Exhausted {
+      message,
+      retry_after,
+    } => HttpResponse::TooManyRequests()
+      .insert_header((RETRY_AFTER, retry_after.to_string()))
+      .body(message),
+    Error::Crashed(msg) => HttpResponse::Gone().body(msg),
+    Error::Blocked(msg) => HttpResponse::Conflict().body(msg),
     error => HttpResponse::BadRequest().body(error.to_string()),
   }
 }
@@ -22,3 +33,7 @@ pub fn from_image(res: Result<Vec<u8>, Error>) -> HttpResponse {
 pub fn from_unit(res: Result<(), Error>) -> HttpResponse {
   res.map_or_else(from_error, |()| HttpResponse::Ok().finish())
 }
+
+pub fn from_json<T: Serialize>(res: Result<T, Error>) -> HttpResponse {
+  res.map_or_else(from_error, |value| HttpResponse::Ok().json(value))
+}
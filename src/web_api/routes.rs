@@ -2,10 +2,17 @@ use std::sync::Arc;
 
 use actix_web::{HttpResponse, web};
 use chaser_oxide::Browser;
+use serde::de::DeserializeOwned;
 use serde_json::json;
 
-use crate::browser::tab;
-use crate::browser::tab::dto::{ClickDto, ExecuteDto, ExistsDto, ExtractDto, FillDto, OpenDto};
+use crate::browser::session::dto::CreateSessionDto;
+use crate::browser::tab::dto::{
+  AnswerDialogDto, BlockDto, ClickDto, ConsoleQueryDto, DeleteCookiesDto, DeleteRoutesDto,
+  ExecuteDto, ExistsDto, ExpirationDto, ExtractDto, FillDto, HeadersDto, HttpCredentialsDto,
+  NavigateDto, OpenDto, RouteDto, SetCookiesDto, WaitForResponseDto,
+};
+use crate::browser::{session, tab};
+use crate::models::{Error, ErrorInfo};
 use crate::web_api::response;
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
@@ -25,12 +32,48 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
           "/open",
           web::post().to(
             |req: web::Json<OpenDto>, browser: web::Data<Arc<Browser>>| async move {
-              response::from_string(
-                tab::api::open(browser.get_ref().clone(), req.into_inner()).await,
-              )
+              response::from_json(tab::api::open(browser.get_ref().clone(), req.into_inner()).await)
             },
           ),
         ))
+        .service(
+          web::scope("/sessions")
+            .route(
+              "",
+              web::post().to(
+                |body: web::Bytes, browser: web::Data<Arc<Browser>>| async move {
+                  match optional_json::<CreateSessionDto>(&body) {
+                    Ok(dto) => response::from_json(
+                      session::api::create(browser.get_ref().clone(), dto).await,
+                    ),
+                    Err(e) => response::from_error(e),
+                  }
+                },
+              ),
+            )
+            .route(
+              "",
+              web::get().to(|| async { HttpResponse::Ok().json(session::api::list().await) }),
+            )
+            .route(
+              "/{id}/close",
+              web::delete().to(|id: web::Path<String>| async move {
+                response::from_unit(session::api::close(&id).await)
+              }),
+            ),
+        )
+        .route(
+          "/tabs",
+          web::get().to(|| async { HttpResponse::Ok().json(tab::api::list().await) }),
+        )
+        .route(
+          "/proxies",
+          web::get().to(|| async { HttpResponse::Ok().json(tab::proxies::stats()) }),
+        )
+        .route(
+          "/tabs/expirations",
+          web::get().to(|| async { HttpResponse::Ok().json(tab::reaper::stats().await) }),
+        )
         .service(
           web::scope("/tabs/{id}")
             .route(
@@ -39,6 +82,167 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
                 response::from_unit(tab::api::close(&id).await)
               }),
             )
+            .route(
+              "/expiration",
+              web::patch().to(
+                |req: web::Json<ExpirationDto>, id: web::Path<String>| async move {
+                  response::from_json(tab::api::reschedule(&id, req.into_inner()).await)
+                },
+              ),
+            )
+            .route(
+              "/navigate",
+              web::post().to(
+                |req: web::Json<NavigateDto>, id: web::Path<String>| async move {
+                  response::from_json(tab::api::navigate(&id, req.into_inner()).await)
+                },
+              ),
+            )
+            .route(
+              "/back",
+              web::post().to(|id: web::Path<String>| async move {
+                response::from_json(tab::api::back(&id).await)
+              }),
+            )
+            .route(
+              "/forward",
+              web::post().to(|id: web::Path<String>| async move {
+                response::from_json(tab::api::forward(&id).await)
+              }),
+            )
+            .route(
+              "/reload",
+              web::post().to(|id: web::Path<String>| async move {
+                response::from_json(tab::api::reload(&id).await)
+              }),
+            )
+            .route(
+              "/cookies",
+              web::get().to(|id: web::Path<String>| async move {
+                response::from_json(tab::api::get_cookies(&id).await)
+              }),
+            )
+            .route(
+              "/cookies",
+              web::post().to(
+                |req: web::Json<SetCookiesDto>, id: web::Path<String>| async move {
+                  response::from_unit(tab::api::set_cookies(&id, req.into_inner()).await)
+                },
+              ),
+            )
+            .route(
+              "/cookies",
+              web::delete().to(
+                |query: web::Query<DeleteCookiesDto>, id: web::Path<String>| async move {
+                  response::from_json(tab::api::delete_cookies(&id, query.into_inner()).await)
+                },
+              ),
+            )
+            .route(
+              "/storage-state",
+              web::get().to(|id: web::Path<String>| async move {
+                response::from_json(tab::api::storage_state(&id).await)
+              }),
+            )
+            .route(
+              "/wait-for-response",
+              web::post().to(
+                |req: web::Json<WaitForResponseDto>, id: web::Path<String>| async move {
+                  response::from_json(tab::api::wait_for_response(&id, req.into_inner()).await)
+                },
+              ),
+            )
+            .route(
+              "/har",
+              web::get().to(|id: web::Path<String>| async move {
+                response::from_json(tab::api::har(&id).await)
+              }),
+            )
+            .route(
+              "/dialogs",
+              web::get().to(|id: web::Path<String>| async move {
+                response::from_json(tab::api::dialogs(&id).await)
+              }),
+            )
+            .route(
+              "/dialogs/{dialog_id}",
+              web::post().to(
+                |req: web::Json<AnswerDialogDto>, path: web::Path<(String, String)>| async move {
+                  let (id, dialog_id) = path.into_inner();
+                  response::from_unit(
+                    tab::api::answer_dialog(&id, &dialog_id, req.into_inner()).await,
+                  )
+                },
+              ),
+            )
+            .route(
+              "/errors",
+              web::get().to(|id: web::Path<String>| async move {
+                response::from_json(tab::api::errors(&id).await)
+              }),
+            )
+            .route(
+              "/console",
+              web::get().to(
+                |query: web::Query<ConsoleQueryDto>, id: web::Path<String>| async move {
+                  response::from_json(tab::api::console(&id, query.into_inner()).await)
+                },
+              ),
+            )
+            .route(
+              "/network/rules",
+              web::get().to(|id: web::Path<String>| async move {
+                response::from_json(tab::api::network_rules(&id).await)
+              }),
+            )
+            .route(
+              "/network/rules",
+              web::put().to(
+                |req: web::Json<BlockDto>, id: web::Path<String>| async move {
+                  response::from_json(tab::api::set_network_rules(&id, req.into_inner()).await)
+                },
+              ),
+            )
+            .route(
+              "/headers",
+              web::put().to(
+                |req: web::Json<HeadersDto>, id: web::Path<String>| async move {
+                  response::from_unit(tab::api::set_headers(&id, req.into_inner()).await)
+                },
+              ),
+            )
+            .route(
+              "/http-credentials",
+              web::put().to(
+                |req: web::Json<HttpCredentialsDto>, id: web::Path<String>| async move {
+                  response::from_unit(
+                    tab::api::set_http_credentials(&id, Some(req.into_inner())).await,
+                  )
+                },
+              ),
+            )
+            .route(
+              "/http-credentials",
+              web::delete().to(|id: web::Path<String>| async move {
+                response::from_unit(tab::api::set_http_credentials(&id, None).await)
+              }),
+            )
+            .route(
+              "/routes",
+              web::post().to(
+                |req: web::Json<RouteDto>, id: web::Path<String>| async move {
+                  response::from_json(tab::api::add_route(&id, req.into_inner()).await)
+                },
+              ),
+            )
+            .route(
+              "/routes",
+              web::delete().to(
+                |query: web::Query<DeleteRoutesDto>, id: web::Path<String>| async move {
+                  response::from_unit(tab::api::delete_routes(&id, query.into_inner()).await)
+                },
+              ),
+            )
             .route(
               "/fill",
               web::post().to(
@@ -71,7 +275,11 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
               "/exists",
               web::post().to(
                 |req: web::Json<ExistsDto>, id: web::Path<String>| async move {
-                  HttpResponse::Ok().body(tab::api::exists(&id, req.into_inner()).await.to_string())
+                  response::from_string(
+                    tab::api::exists(&id, req.into_inner())
+                      .await
+                      .map(|exists| exists.to_string()),
+                  )
                 },
               ),
             )
@@ -95,3 +303,17 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
     )
     .default_service(web::to(HttpResponse::NotFound));
 }
+
+/// Parses an optional JSON body, falling back to the default payload when the body is empty.
+fn optional_json<T: DeserializeOwned + Default>(body: &[u8]) -> Result<T, Error> {
+  if body.iter().all(u8::is_ascii_whitespace) {
+    return Ok(T::default());
+  }
+
+  serde_json::from_slice(body).map_err(|e| {
+    Error::Operation(ErrorInfo {
+      message: format!("Invalid JSON payload: {e}"),
+      code: None,
+    })
+  })
+}
@@ -3,10 +3,14 @@ use chaser_oxide::{Browser, Handler};
 use futures::StreamExt;
 use std::{env, sync::Arc};
 
+use crate::browser::{session, tab};
+
 /// Starts an HTTP server with the provided browser instance.
 ///
 /// This function configures and starts an Actix web server with CORS support,
 /// request logging via `TracingLogger`, and routes defined in the application.
+/// A background tab reaper closes expired tabs while the server runs, and every
+/// remaining tab and session is closed once the server stops.
 /// The server host and port can be configured via environment variables
 /// `SERVER_HOST` and `SERVER_PORT`, with defaults of "127.0.0.1" and "8080"
 /// respectively.
@@ -32,12 +36,13 @@ pub async fn run(browser: Arc<Browser>, mut handler: Handler) -> std::io::Result
 
   // Spawn handler event loop as shown in chaser-oxide documentation
   tokio::spawn(async move { while (handler.next().await).is_some() {} });
+  tokio::spawn(tab::reaper::run());
 
   tracing::info!("Starting server at http://{}:{}", host, port);
 
   actix_web::HttpServer::new(move || {
     let cors = actix_cors::Cors::default()
-      .allowed_methods(vec!["GET", "POST", "PUT", "DELETE"])
+      .allowed_methods(vec!["GET", "POST", "PUT", "PATCH", "DELETE"])
       .allowed_headers(vec![
         "Content-Type",
         "Authorization",
@@ -54,5 +59,10 @@ pub async fn run(browser: Arc<Browser>, mut handler: Handler) -> std::io::Result
   })
   .bind(format!("{host}:{port}"))?
   .run()
-  .await
+  .await?;
+
+  tab::reaper::drain().await;
+  session::api::drain().await;
+
+  Ok(())
 }